@@ -1,10 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use solana_program::{
     program::invoke_signed,
     sysvar::clock::Clock,
 };
 use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_MODEL_USAGE};
+use crate::staking::StakePool;
+use crate::data_marketplace::{self, PaymentAccounts, PaymentSplit};
+use crate::compliance::{Blocklist, BLOCKLIST_SEED};
+use crate::events::*;
 
 // Constants for AI model marketplace
 pub const MODEL_VERSION: u8 = 1;
@@ -98,6 +103,13 @@ pub struct ModelMarketplaceConfig {
     pub platform_fee_percentage: u8,
     pub platform_fee_recipient: Pubkey,
     
+    // Mint purchases are paid in
+    pub payment_mint: Pubkey,
+    
+    // Share of the platform fee routed to DATA stakers
+    pub staking_fee_percentage: u8,
+    pub staking_reward_vault: Pubkey,
+    
     // Statistics
     pub total_models: u64,
     pub total_usages: u64,
@@ -107,6 +119,12 @@ pub struct ModelMarketplaceConfig {
     pub version: u8,
 }
 
+impl ModelMarketplaceConfig {
+    pub fn split_payment(&self, amount: u64) -> Result<PaymentSplit> {
+        data_marketplace::split_payment(amount, self.platform_fee_percentage, self.staking_fee_percentage)
+    }
+}
+
 // Context for registering an AI model
 #[derive(Accounts)]
 pub struct RegisterModel<'info> {
@@ -137,7 +155,10 @@ pub struct UseModel<'info> {
     #[account(mut)]
     pub model: Account<'info, AiModel>,
     
-    #[account(mut)]
+    /// CHECK: Model owner, checked against the model
+    #[account(
+        constraint = model_owner.key() == model.owner @ DataChainError::Unauthorized
+    )]
     pub model_owner: AccountInfo<'info>,
     
     #[account(
@@ -147,28 +168,58 @@ pub struct UseModel<'info> {
     )]
     pub usage_record: Account<'info, ModelUsage>,
     
+    #[account(
+        constraint = payment_mint.key() == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
     #[account(
         mut,
+        constraint = user_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = user_token.owner == user.key() @ DataChainError::Unauthorized
     )]
-    pub user_token: Account<'info, TokenAccount>,
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
+        constraint = owner_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = owner_token.owner == model_owner.key() @ DataChainError::Unauthorized
     )]
-    pub owner_token: Account<'info, TokenAccount>,
+    pub owner_token: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
+        constraint = platform_fee_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = platform_fee_token.owner == marketplace_config.platform_fee_recipient @ DataChainError::Unauthorized
     )]
-    pub platform_fee_token: Account<'info, TokenAccount>,
+    pub platform_fee_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_reward_vault.key() == marketplace_config.staking_reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub staking_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(mut)]
     pub marketplace_config: Account<'info, ModelMarketplaceConfig>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_MODEL_USAGE) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&user.key()) @ DataChainError::WalletBlocked,
+        constraint = !blocklist.is_blocked(&model.owner) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub clock: Sysvar<'info, Clock>,
@@ -255,6 +306,13 @@ pub fn register_model(
     // Update marketplace stats
     marketplace_config.total_models = marketplace_config.total_models.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(ModelRegistered {
+        model: model.key(),
+        owner: model.owner,
+        price_per_query: model.price_per_query,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("AI Model registered: {}", model.name);
     
     Ok(())
@@ -281,49 +339,22 @@ pub fn use_model(
         return err!(DataChainError::ModelNotFound);
     }
     
-    // Calculate fees
+    // Pay the stakers, the platform and the model owner their shares
     let usage_amount = model.price_per_query;
-    let platform_fee = (usage_amount as u128)
-        .checked_mul(marketplace_config.platform_fee_percentage as u128)
-        .ok_or(DataChainError::InvalidParameters)?
-        .checked_div(100)
-        .ok_or(DataChainError::InvalidParameters)? as u64;
-    
-    let owner_amount = usage_amount.checked_sub(platform_fee).ok_or(DataChainError::InvalidParameters)?;
-    
-    // Transfer platform fee
-    {
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.user_token.to_account_info(),
-            to: ctx.accounts.platform_fee_token.to_account_info(),
-            authority: user.to_account_info(),
-        };
-        
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-            ),
-            platform_fee,
-        )?;
-    }
-    
-    // Transfer payment to model owner
-    {
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.user_token.to_account_info(),
-            to: ctx.accounts.owner_token.to_account_info(),
-            authority: user.to_account_info(),
-        };
-        
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-            ),
-            owner_amount,
-        )?;
-    }
+    let split = marketplace_config.split_payment(usage_amount)?;
+    
+    PaymentAccounts {
+        payer: user,
+        payer_token: &ctx.accounts.user_token,
+        owner_token: Some(&ctx.accounts.owner_token),
+        platform_fee_token: &ctx.accounts.platform_fee_token,
+        staking_reward_vault: ctx.accounts.staking_reward_vault.as_ref(),
+        payment_mint: &ctx.accounts.payment_mint,
+        token_program: &ctx.accounts.token_program,
+        revenue_split: None,
+        recipient_tokens: &[],
+        signer_seeds: &[],
+    }.pay(&split)?;
     
     // Create usage record
     usage_record.user = user.key();
@@ -341,6 +372,17 @@ pub fn use_model(
     marketplace_config.total_usages = marketplace_config.total_usages.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     marketplace_config.total_volume = marketplace_config.total_volume.checked_add(usage_amount).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(ModelUsed {
+        model: model.key(),
+        user: user.key(),
+        owner: model.owner,
+        price: usage_amount,
+        platform_fee: split.platform_fee,
+        staking_fee: split.staking_amount,
+        owner_amount: split.seller_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("AI Model used: {} by {}", model.name, user.key());
     
     Ok(())
@@ -386,6 +428,13 @@ pub fn rate_model(
     model.rating_sum = model.rating_sum.checked_add(rating_value as u64).ok_or(DataChainError::InvalidParameters)?;
     model.rating_count = model.rating_count.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(ModelRated {
+        model: model.key(),
+        rater: rating.rater,
+        rating: rating_value,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("AI Model rated: {} with rating {}", model.name, rating_value);
     
     Ok(())
@@ -405,11 +454,22 @@ pub fn initialize_model_marketplace(
     marketplace_config.authority = ctx.accounts.authority.key();
     marketplace_config.platform_fee_percentage = platform_fee_percentage;
     marketplace_config.platform_fee_recipient = ctx.accounts.platform_fee_recipient.key();
+    marketplace_config.payment_mint = ctx.accounts.payment_mint.key();
+    marketplace_config.staking_fee_percentage = 0;
+    marketplace_config.staking_reward_vault = Pubkey::default();
     marketplace_config.total_models = 0;
     marketplace_config.total_usages = 0;
     marketplace_config.total_volume = 0;
     marketplace_config.version = MODEL_VERSION;
     
+    emit!(MarketplaceInitialized {
+        marketplace_config: marketplace_config.key(),
+        authority: marketplace_config.authority,
+        platform_fee_percentage,
+        platform_fee_recipient: marketplace_config.platform_fee_recipient,
+        payment_mint: marketplace_config.payment_mint,
+    });
+    
     msg!("AI Model marketplace initialized with {}% fee", platform_fee_percentage);
     
     Ok(())
@@ -422,6 +482,8 @@ pub struct InitializeModelMarketplace<'info> {
     
     pub platform_fee_recipient: AccountInfo<'info>,
     
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
     #[account(
         init,
         payer = authority,
@@ -431,4 +493,47 @@ pub struct InitializeModelMarketplace<'info> {
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
-} 
\ No newline at end of file
+} 
+
+// Route a share of the platform fee to a stake pool's reward vault
+pub fn set_model_staking_fee_share(
+    ctx: Context<SetModelStakingFeeShare>,
+    staking_fee_percentage: u8,
+) -> Result<()> {
+    if staking_fee_percentage > 100 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    
+    marketplace_config.staking_fee_percentage = staking_fee_percentage;
+    marketplace_config.staking_reward_vault = ctx.accounts.stake_pool.reward_vault;
+    
+    emit!(StakingFeeShareSet {
+        marketplace_config: marketplace_config.key(),
+        stake_pool: ctx.accounts.stake_pool.key(),
+        staking_fee_percentage,
+        staking_reward_vault: marketplace_config.staking_reward_vault,
+    });
+    
+    msg!("AI Model marketplace routes {}% of platform fees to stakers", staking_fee_percentage);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetModelStakingFeeShare<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = marketplace_config.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub marketplace_config: Account<'info, ModelMarketplaceConfig>,
+    
+    // The pool's reward vault must hold the mint the marketplace is paid in
+    #[account(
+        constraint = stake_pool.stake_mint == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
\ No newline at end of file
@@ -0,0 +1,535 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solana_program::{
+    keccak,
+    sysvar::clock::Clock,
+};
+use crate::errors::DataChainError;
+use crate::events::*;
+
+// Constants for merkle airdrops
+pub const AIRDROP_VERSION: u8 = 1;
+pub const AIRDROP_AUTHORITY_SEED: &[u8] = b"airdrop_authority";
+pub const AIRDROP_VAULT_SEED: &[u8] = b"airdrop_vault";
+pub const AIRDROP_CLAIM_STATUS_SEED: &[u8] = b"airdrop_claim_status";
+pub const MAX_AIRDROP_RECIPIENTS: u64 = 32_768;
+pub const AIRDROP_BITMAP_SIZE: usize = (MAX_AIRDROP_RECIPIENTS / 8) as usize;
+pub const MAX_MERKLE_PROOF_LENGTH: usize = 16;
+
+// Airdrop distributor structure
+#[account]
+pub struct AirdropDistributor {
+    // Authority that funded the airdrop (and may claw it back)
+    pub authority: Pubkey,
+    
+    // Token mint and the program-owned vault holding the airdrop
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    
+    // Root of the merkle tree of (index, claimant, amount) leaves
+    pub merkle_root: [u8; 32],
+    
+    // Allocation info
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub num_recipients: u64,
+    pub num_claimed: u64,
+    
+    // Claim window
+    pub expiry_timestamp: i64,
+    pub clawed_back: bool,
+    
+    // Bump of the vault authority PDA
+    pub authority_bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+// Claim status bitmap, one bit per leaf index
+#[account]
+pub struct AirdropClaimStatus {
+    // Distributor the bitmap belongs to
+    pub distributor: Pubkey,
+    
+    // Claimed bits
+    pub bitmap: Vec<u8>,
+}
+
+impl AirdropClaimStatus {
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+    
+    pub fn set_claimed(&mut self, index: u64) {
+        self.bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+// Leaf committing to a claimant's index and allocation
+pub fn airdrop_leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ]).0
+}
+
+// Verify a merkle proof for a leaf, hashing sibling pairs in sorted order
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    
+    for node in proof.iter() {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).0
+        } else {
+            keccak::hashv(&[node, &computed]).0
+        };
+    }
+    
+    computed == *root
+}
+
+// Context for creating and funding an airdrop
+#[derive(Accounts)]
+pub struct CreateAirdrop<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = authority_token.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = authority_token.owner == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub authority_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<AirdropDistributor>(),
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<AirdropClaimStatus>() + AIRDROP_BITMAP_SIZE,
+        seeds = [AIRDROP_CLAIM_STATUS_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub claim_status: Account<'info, AirdropClaimStatus>,
+    
+    /// CHECK: PDA signing for the airdrop vault
+    #[account(
+        seeds = [AIRDROP_AUTHORITY_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub airdrop_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [AIRDROP_VAULT_SEED, distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = airdrop_authority,
+    )]
+    pub airdrop_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for claiming an airdrop allocation
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    pub claimant: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = !distributor.clawed_back @ DataChainError::AirdropExpired
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+    
+    #[account(
+        mut,
+        seeds = [AIRDROP_CLAIM_STATUS_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub claim_status: Account<'info, AirdropClaimStatus>,
+    
+    /// CHECK: PDA signing for the airdrop vault
+    #[account(
+        seeds = [AIRDROP_AUTHORITY_SEED, distributor.key().as_ref()],
+        bump = distributor.authority_bump
+    )]
+    pub airdrop_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = airdrop_vault.key() == distributor.vault @ DataChainError::InvalidParameters
+    )]
+    pub airdrop_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = claimant_token.mint == distributor.mint @ DataChainError::InvalidParameters,
+        constraint = claimant_token.owner == claimant.key() @ DataChainError::Unauthorized
+    )]
+    pub claimant_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for clawing back unclaimed tokens after expiry
+#[derive(Accounts)]
+pub struct ClawbackAirdrop<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = distributor.authority == authority.key() @ DataChainError::Unauthorized,
+        constraint = !distributor.clawed_back @ DataChainError::InvalidParameters
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+    
+    /// CHECK: PDA signing for the airdrop vault
+    #[account(
+        seeds = [AIRDROP_AUTHORITY_SEED, distributor.key().as_ref()],
+        bump = distributor.authority_bump
+    )]
+    pub airdrop_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = airdrop_vault.key() == distributor.vault @ DataChainError::InvalidParameters
+    )]
+    pub airdrop_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = refund_token.mint == distributor.mint @ DataChainError::InvalidParameters,
+        constraint = refund_token.owner == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub refund_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of airdrop functions
+pub fn create_airdrop(
+    ctx: Context<CreateAirdrop>,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    num_recipients: u64,
+    expiry_timestamp: i64,
+) -> Result<()> {
+    // Validate airdrop parameters
+    if total_amount == 0 || num_recipients == 0 || num_recipients > MAX_AIRDROP_RECIPIENTS {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if expiry_timestamp <= ctx.accounts.clock.unix_timestamp {
+        return err!(DataChainError::InvalidTimestamp);
+    }
+    
+    // Fund the airdrop vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.authority_token.to_account_info(),
+        to: ctx.accounts.airdrop_vault.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        total_amount,
+    )?;
+    
+    let distributor = &mut ctx.accounts.distributor;
+    let claim_status = &mut ctx.accounts.claim_status;
+    
+    // Initialize distributor
+    distributor.authority = ctx.accounts.authority.key();
+    distributor.mint = ctx.accounts.mint.key();
+    distributor.vault = ctx.accounts.airdrop_vault.key();
+    distributor.merkle_root = merkle_root;
+    distributor.total_amount = total_amount;
+    distributor.claimed_amount = 0;
+    distributor.num_recipients = num_recipients;
+    distributor.num_claimed = 0;
+    distributor.expiry_timestamp = expiry_timestamp;
+    distributor.clawed_back = false;
+    distributor.authority_bump = *ctx.bumps.get("airdrop_authority").ok_or(DataChainError::SystemError)?;
+    distributor.version = AIRDROP_VERSION;
+    
+    // Initialize claim status bitmap
+    claim_status.distributor = distributor.key();
+    claim_status.bitmap = vec![0; num_recipients.div_ceil(8) as usize];
+    
+    emit!(AirdropCreated {
+        distributor: distributor.key(),
+        mint: distributor.mint,
+        merkle_root,
+        total_amount,
+        num_recipients,
+        expiry_timestamp,
+    });
+    
+    msg!("Airdrop created: {} DATA for {} recipients", total_amount, num_recipients);
+    
+    Ok(())
+}
+
+pub fn claim_airdrop(
+    ctx: Context<ClaimAirdrop>,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    let claim_status = &mut ctx.accounts.claim_status;
+    let claimant = ctx.accounts.claimant.key();
+    let clock = &ctx.accounts.clock;
+    
+    if clock.unix_timestamp >= distributor.expiry_timestamp {
+        return err!(DataChainError::AirdropExpired);
+    }
+    
+    if index >= distributor.num_recipients || proof.len() > MAX_MERKLE_PROOF_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if claim_status.is_claimed(index) {
+        return err!(DataChainError::AirdropAlreadyClaimed);
+    }
+    
+    // Verify the leaf for this claimant against the merkle root
+    let leaf = airdrop_leaf(index, &claimant, amount);
+    
+    if !verify_merkle_proof(&proof, &distributor.merkle_root, leaf) {
+        return err!(DataChainError::InvalidMerkleProof);
+    }
+    
+    let claimed_amount = distributor.claimed_amount.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+    if claimed_amount > distributor.total_amount {
+        return err!(DataChainError::InsufficientBalance);
+    }
+    
+    // Release tokens from the vault
+    let distributor_key = distributor.key();
+    let seeds = &[
+        AIRDROP_AUTHORITY_SEED,
+        distributor_key.as_ref(),
+        &[distributor.authority_bump],
+    ];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.airdrop_vault.to_account_info(),
+        to: ctx.accounts.claimant_token.to_account_info(),
+        authority: ctx.accounts.airdrop_authority.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+        amount,
+    )?;
+    
+    claim_status.set_claimed(index);
+    distributor.claimed_amount = claimed_amount;
+    distributor.num_claimed = distributor.num_claimed.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(AirdropClaimed {
+        distributor: distributor.key(),
+        claimant,
+        index,
+        amount,
+    });
+    
+    msg!("Airdrop of {} DATA tokens claimed by {}", amount, claimant);
+    
+    Ok(())
+}
+
+pub fn clawback_airdrop(
+    ctx: Context<ClawbackAirdrop>,
+) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    let clock = &ctx.accounts.clock;
+    
+    if clock.unix_timestamp < distributor.expiry_timestamp {
+        return err!(DataChainError::AirdropNotExpired);
+    }
+    
+    // Return everything left in the vault
+    let unclaimed = ctx.accounts.airdrop_vault.amount;
+    
+    if unclaimed > 0 {
+        let distributor_key = distributor.key();
+        let seeds = &[
+            AIRDROP_AUTHORITY_SEED,
+            distributor_key.as_ref(),
+            &[distributor.authority_bump],
+        ];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.airdrop_vault.to_account_info(),
+            to: ctx.accounts.refund_token.to_account_info(),
+            authority: ctx.accounts.airdrop_authority.to_account_info(),
+        };
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            unclaimed,
+        )?;
+    }
+    
+    distributor.clawed_back = true;
+    
+    emit!(AirdropClawedBack {
+        distributor: distributor.key(),
+        amount: unclaimed,
+    });
+    
+    msg!("Airdrop clawed back, {} unclaimed DATA tokens returned", unclaimed);
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[a, b]).0
+        } else {
+            keccak::hashv(&[b, a]).0
+        }
+    }
+    
+    // Build a tree bottom up, carrying an unpaired last node up a level; returns the root and each leaf's proof
+    fn merkle_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+        let mut proofs = vec![Vec::new(); leaves.len()];
+        let mut positions: Vec<usize> = (0..leaves.len()).collect();
+        let mut level = leaves.to_vec();
+        
+        while level.len() > 1 {
+            for (leaf, position) in positions.iter_mut().enumerate() {
+                let sibling = *position ^ 1;
+                if sibling < level.len() {
+                    proofs[leaf].push(level[sibling]);
+                }
+                *position /= 2;
+            }
+            
+            level = level.chunks(2)
+                .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+        }
+        
+        (level[0], proofs)
+    }
+    
+    fn claimants(count: u64) -> Vec<(u64, Pubkey, u64)> {
+        (0..count).map(|index| (index, Pubkey::new_unique(), 1_000 * (index + 1))).collect()
+    }
+    
+    #[test]
+    fn every_leaf_verifies_against_the_root() {
+        for count in [1, 2, 3, 5, 8] {
+            let claimants = claimants(count);
+            let leaves: Vec<[u8; 32]> = claimants.iter().map(|(index, claimant, amount)| airdrop_leaf(*index, claimant, *amount)).collect();
+            let (root, proofs) = merkle_tree(&leaves);
+            
+            for (leaf, proof) in leaves.iter().zip(&proofs) {
+                assert!(verify_merkle_proof(proof, &root, *leaf));
+            }
+        }
+    }
+    
+    #[test]
+    fn single_leaf_tree_has_an_empty_proof() {
+        let claimant = Pubkey::new_unique();
+        let leaf = airdrop_leaf(0, &claimant, 500);
+        
+        assert!(verify_merkle_proof(&[], &leaf, leaf));
+        assert!(!verify_merkle_proof(&[], &leaf, airdrop_leaf(0, &claimant, 501)));
+    }
+    
+    #[test]
+    fn altered_claims_are_rejected() {
+        let claimants = claimants(4);
+        let leaves: Vec<[u8; 32]> = claimants.iter().map(|(index, claimant, amount)| airdrop_leaf(*index, claimant, *amount)).collect();
+        let (root, proofs) = merkle_tree(&leaves);
+        let (index, claimant, amount) = claimants[1];
+        
+        // Wrong amount, claimant or index
+        assert!(!verify_merkle_proof(&proofs[1], &root, airdrop_leaf(index, &claimant, amount + 1)));
+        assert!(!verify_merkle_proof(&proofs[1], &root, airdrop_leaf(index, &Pubkey::new_unique(), amount)));
+        assert!(!verify_merkle_proof(&proofs[1], &root, airdrop_leaf(index + 1, &claimant, amount)));
+        
+        // Another leaf's proof, or a truncated or extended proof
+        assert!(!verify_merkle_proof(&proofs[2], &root, leaves[1]));
+        assert!(!verify_merkle_proof(&proofs[1][..1], &root, leaves[1]));
+        
+        let mut extended = proofs[1].clone();
+        extended.push(leaves[0]);
+        assert!(!verify_merkle_proof(&extended, &root, leaves[1]));
+    }
+    
+    #[test]
+    fn claims_verify_the_derived_leaf_not_a_raw_hash() {
+        let claimants = claimants(4);
+        let leaves: Vec<[u8; 32]> = claimants.iter().map(|(index, claimant, amount)| airdrop_leaf(*index, claimant, *amount)).collect();
+        let (root, _) = merkle_tree(&leaves);
+        
+        // The proof check alone accepts an inner node with the rest of its path
+        let inner = hash_pair(&leaves[0], &leaves[1]);
+        assert!(verify_merkle_proof(&[hash_pair(&leaves[2], &leaves[3])], &root, inner));
+        
+        // Claims never pass a hash: the leaf is derived from a 48 byte preimage, while inner
+        // nodes hash 64 bytes, so no (index, claimant, amount) can reproduce an inner node
+        let (index, claimant, amount) = claimants[0];
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&index.to_le_bytes());
+        preimage.extend_from_slice(claimant.as_ref());
+        preimage.extend_from_slice(&amount.to_le_bytes());
+        assert_eq!(preimage.len(), 48);
+        assert_eq!(airdrop_leaf(index, &claimant, amount), keccak::hash(&preimage).0);
+    }
+    
+    #[test]
+    fn claim_bits_are_independent() {
+        let mut claim_status = AirdropClaimStatus {
+            distributor: Pubkey::default(),
+            bitmap: vec![0; AIRDROP_BITMAP_SIZE],
+        };
+        
+        for index in [0, 7, 8, MAX_AIRDROP_RECIPIENTS - 1] {
+            assert!(!claim_status.is_claimed(index));
+            claim_status.set_claimed(index);
+            assert!(claim_status.is_claimed(index));
+        }
+        
+        assert!(!claim_status.is_claimed(1));
+        assert!(!claim_status.is_claimed(9));
+        assert!(!claim_status.is_claimed(MAX_AIRDROP_RECIPIENTS - 2));
+    }
+}
@@ -0,0 +1,451 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_DATASET_PURCHASES};
+use crate::compliance::{Blocklist, BLOCKLIST_SEED};
+use crate::data_marketplace::{
+    self, Dataset, MarketplaceConfig, PurchaseRecord, PaymentAccounts, PricingMode, DeliveryStatus,
+    PURCHASE_RECORD_SPACE, PURCHASE_SEED, MAX_DATASET_NAME_LENGTH,
+};
+use crate::events::*;
+
+// Constants for dataset bundles
+pub const BUNDLE_VERSION: u8 = 1;
+pub const BUNDLE_SEED: &[u8] = b"bundle";
+pub const MIN_BUNDLE_DATASETS: usize = 2;
+pub const MAX_BUNDLE_DATASETS: usize = 10;
+pub const BUNDLE_SPACE: usize = 8 + std::mem::size_of::<DatasetBundle>() + MAX_DATASET_NAME_LENGTH + (32 * MAX_BUNDLE_DATASETS);
+
+// Accounts passed per bundle member when purchasing: dataset, purchase record, owner token account
+pub const BUNDLE_MEMBER_ACCOUNTS: usize = 3;
+
+// Pack of datasets sold together at a single price
+#[account]
+pub struct DatasetBundle {
+    // Bundle creator
+    pub creator: Pubkey,
+    
+    // Marketplace the bundle is sold on
+    pub marketplace_config: Pubkey,
+    
+    // Bundle metadata
+    pub name: String,
+    
+    // Member datasets, in the order their accounts are passed
+    pub datasets: Vec<Pubkey>,
+    
+    // Price of the whole bundle
+    pub price: u64,
+    
+    // Bundle statistics
+    pub purchases: u64,
+    
+    // Creation timestamp
+    pub created_at: i64,
+    
+    // Bump of the bundle PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+// Address of the bundle a creator registered under a name
+pub fn find_bundle_address(creator: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BUNDLE_SEED, creator.as_ref(), &data_marketplace::dataset_name_hash(name)],
+        &crate::ID,
+    )
+}
+
+// Divide an amount in proportion to weights, rounded down, with the rounding dust going to the first entry with a weight
+pub fn pro_rata(amount: u64, weights: &[u64]) -> Result<Vec<u64>> {
+    let total_weight = weights.iter().try_fold(0u128, |total, weight| total.checked_add(*weight as u128))
+        .ok_or(DataChainError::InvalidParameters)?;
+    
+    if total_weight == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let mut shares = Vec::with_capacity(weights.len());
+    for weight in weights {
+        let share = (amount as u128)
+            .checked_mul(*weight as u128)
+            .ok_or(DataChainError::InvalidParameters)?
+            .checked_div(total_weight)
+            .ok_or(DataChainError::InvalidParameters)? as u64;
+        shares.push(share);
+    }
+    
+    let distributed = shares.iter().try_fold(0u64, |total, share| total.checked_add(*share))
+        .ok_or(DataChainError::InvalidParameters)?;
+    let dust = amount.checked_sub(distributed).ok_or(DataChainError::InvalidParameters)?;
+    
+    let first_weighted = weights.iter().position(|weight| *weight > 0).ok_or(DataChainError::InvalidParameters)?;
+    shares[first_weighted] = shares[first_weighted].checked_add(dust).ok_or(DataChainError::InvalidParameters)?;
+    
+    Ok(shares)
+}
+
+// Context for creating a bundle; member datasets are passed as remaining accounts, followed by the signing owners other than the creator
+#[derive(Accounts)]
+pub struct CreateBundle<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    /// CHECK: Bundle PDA, created in the instruction
+    #[account(mut)]
+    pub bundle: AccountInfo<'info>,
+    
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for purchasing a bundle; each member's dataset, purchase record and owner token account follow as remaining accounts
+#[derive(Accounts)]
+pub struct PurchaseBundle<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = bundle.marketplace_config == marketplace_config.key() @ DataChainError::InvalidParameters
+    )]
+    pub bundle: Account<'info, DatasetBundle>,
+    
+    #[account(
+        constraint = payment_mint.key() == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = buyer_token.owner == buyer.key() @ DataChainError::Unauthorized
+    )]
+    pub buyer_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = platform_fee_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = platform_fee_token.owner == marketplace_config.platform_fee_recipient @ DataChainError::Unauthorized
+    )]
+    pub platform_fee_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_reward_vault.key() == marketplace_config.staking_reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub staking_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = marketplace_config.escrow_period == 0 @ DataChainError::BundleEscrowUnsupported
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_DATASET_PURCHASES) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&buyer.key()) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of bundle functions
+
+// Bundles can be assembled by anyone, with each member dataset's owner co-signing
+pub fn create_bundle(
+    ctx: Context<CreateBundle>,
+    name: String,
+    datasets: Vec<Pubkey>,
+    price: u64,
+) -> Result<()> {
+    // Validate input parameters
+    if name.len() > MAX_DATASET_NAME_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if price == 0 {
+        return err!(DataChainError::InvalidDatasetPrice);
+    }
+    
+    if datasets.len() < MIN_BUNDLE_DATASETS || datasets.len() > MAX_BUNDLE_DATASETS {
+        return err!(DataChainError::InvalidBundle);
+    }
+    
+    if ctx.remaining_accounts.len() < datasets.len() {
+        return err!(DataChainError::InvalidBundle);
+    }
+    
+    let creator = &ctx.accounts.creator;
+    let (dataset_infos, owner_signers) = ctx.remaining_accounts.split_at(datasets.len());
+    
+    for (index, (dataset_key, dataset_info)) in datasets.iter().zip(dataset_infos).enumerate() {
+        if datasets[..index].contains(dataset_key) || dataset_info.key() != *dataset_key {
+            return err!(DataChainError::InvalidBundle);
+        }
+        
+        // Every owner agrees to the bundle price by signing, unless they are the creator
+        let dataset = Account::<Dataset>::try_from(dataset_info)?;
+        let owner_signed = dataset.owner == creator.key()
+            || owner_signers.iter().any(|signer| signer.key() == dataset.owner && signer.is_signer);
+        if !owner_signed {
+            return err!(DataChainError::Unauthorized);
+        }
+    }
+    
+    let bundle_info = &ctx.accounts.bundle;
+    let clock = &ctx.accounts.clock;
+    
+    // The bundle address is derived from the creator and name
+    let name_hash = data_marketplace::dataset_name_hash(&name);
+    let (bundle_address, bundle_bump) = find_bundle_address(&creator.key(), &name);
+    if bundle_info.key() != bundle_address {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if *bundle_info.owner == crate::ID || !bundle_info.data_is_empty() {
+        return err!(DataChainError::InvalidBundle);
+    }
+    
+    let seeds = &[
+        BUNDLE_SEED,
+        creator.key.as_ref(),
+        &name_hash,
+        &[bundle_bump],
+    ];
+    
+    data_marketplace::create_pda_account(
+        &creator.to_account_info(),
+        bundle_info,
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        BUNDLE_SPACE,
+        &seeds[..],
+    )?;
+    
+    let bundle = DatasetBundle {
+        creator: creator.key(),
+        marketplace_config: ctx.accounts.marketplace_config.key(),
+        name,
+        datasets,
+        price,
+        purchases: 0,
+        created_at: clock.unix_timestamp,
+        bump: bundle_bump,
+        version: BUNDLE_VERSION,
+    };
+    
+    data_marketplace::write_account(bundle_info, &bundle)?;
+    
+    emit!(BundleCreated {
+        bundle: bundle_info.key(),
+        creator: bundle.creator,
+        price,
+        dataset_count: bundle.datasets.len() as u8,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Bundle created: {} with {} datasets", bundle.name, bundle.datasets.len());
+    
+    Ok(())
+}
+
+// Members must be listed, one-time priced and without a revenue split, and the buyer must not hold a purchase record for any of them.
+// Bundle proceeds are paid out immediately, so bundles cannot be bought while the marketplace escrows payments.
+pub fn purchase_bundle<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseBundle<'info>>,
+) -> Result<()> {
+    let bundle = &ctx.accounts.bundle;
+    let buyer = &ctx.accounts.buyer;
+    let clock = &ctx.accounts.clock;
+    
+    if ctx.remaining_accounts.len() != bundle.datasets.len() * BUNDLE_MEMBER_ACCOUNTS {
+        return err!(DataChainError::InvalidBundle);
+    }
+    
+    // Load and check every member before any funds move
+    let mut members = Vec::with_capacity(bundle.datasets.len());
+    for (dataset_key, member_accounts) in bundle.datasets.iter().zip(ctx.remaining_accounts.chunks(BUNDLE_MEMBER_ACCOUNTS)) {
+        let dataset_info = &member_accounts[0];
+        let purchase_record_info = &member_accounts[1];
+        let owner_token_info = &member_accounts[2];
+        
+        if dataset_info.key() != *dataset_key {
+            return err!(DataChainError::InvalidBundle);
+        }
+        
+        let dataset = Account::<Dataset>::try_from(dataset_info)?;
+        if !dataset.is_listed {
+            return err!(DataChainError::DatasetNotListed);
+        }
+        
+        if dataset.pricing_mode != PricingMode::OneTime as u8 || dataset.has_revenue_split {
+            return err!(DataChainError::InvalidBundle);
+        }
+        
+        if dataset.owner == buyer.key() {
+            return err!(DataChainError::Unauthorized);
+        }
+        
+        if ctx.accounts.blocklist.is_blocked(&dataset.owner) {
+            return err!(DataChainError::WalletBlocked);
+        }
+        
+        let (purchase_record_address, purchase_record_bump) = data_marketplace::find_purchase_record_address(dataset_key, &buyer.key());
+        if purchase_record_info.key() != purchase_record_address {
+            return err!(DataChainError::InvalidParameters);
+        }
+        
+        if *purchase_record_info.owner == crate::ID || !purchase_record_info.data_is_empty() {
+            return err!(DataChainError::AccessAlreadyPurchased);
+        }
+        
+        let owner_token = InterfaceAccount::<TokenAccount>::try_from(owner_token_info)?;
+        if owner_token.mint != ctx.accounts.payment_mint.key() || owner_token.owner != dataset.owner {
+            return err!(DataChainError::Unauthorized);
+        }
+        
+        members.push((dataset, purchase_record_info, owner_token_info, purchase_record_bump));
+    }
+    
+    // Proceeds after the platform fee go to each owner in proportion to their dataset's list price
+    let prices: Vec<u64> = members.iter().map(|(dataset, ..)| dataset.price).collect();
+    let split = ctx.accounts.marketplace_config.split_payment(bundle.price)?;
+    let seller_shares = pro_rata(split.seller_amount, &prices)?;
+    let price_shares = pro_rata(bundle.price, &prices)?;
+    
+    let payment = PaymentAccounts {
+        payer: &ctx.accounts.buyer,
+        payer_token: &ctx.accounts.buyer_token,
+        owner_token: None,
+        platform_fee_token: &ctx.accounts.platform_fee_token,
+        staking_reward_vault: ctx.accounts.staking_reward_vault.as_ref(),
+        payment_mint: &ctx.accounts.payment_mint,
+        token_program: &ctx.accounts.token_program,
+        revenue_split: None,
+        recipient_tokens: &[],
+        signer_seeds: &[],
+    };
+    
+    payment.pay_fees(&split)?;
+    
+    for ((mut dataset, purchase_record_info, owner_token_info, purchase_record_bump), (seller_share, price_share)) in members.into_iter().zip(seller_shares.into_iter().zip(price_shares)) {
+        if seller_share > 0 {
+            payment.transfer(owner_token_info, seller_share)?;
+        }
+        
+        // Create purchase record
+        let dataset_key = dataset.key();
+        let seeds = &[
+            PURCHASE_SEED,
+            dataset_key.as_ref(),
+            buyer.key.as_ref(),
+            &[purchase_record_bump],
+        ];
+        
+        data_marketplace::create_pda_account(
+            &buyer.to_account_info(),
+            purchase_record_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.rent,
+            PURCHASE_RECORD_SPACE,
+            &seeds[..],
+        )?;
+        
+        let purchase_record = PurchaseRecord {
+            buyer: buyer.key(),
+            dataset: dataset_key,
+            purchased_at: clock.unix_timestamp,
+            has_rated: false,
+            price_paid: price_share,
+            expires_at: None,
+            access_scope: dataset.access_scope,
+            version: dataset.latest_version,
+            encryption_key: None,
+            delivery_status: DeliveryStatus::AwaitingKey as u8,
+            encrypted_key: Vec::new(),
+            delivered_at: 0,
+            bump: purchase_record_bump,
+        };
+        
+        data_marketplace::write_account(purchase_record_info, &purchase_record)?;
+        
+        // Update dataset stats
+        dataset.purchases = dataset.purchases.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+        dataset.exit(&crate::ID)?;
+    }
+    
+    // Update bundle and marketplace stats
+    let dataset_count = bundle.datasets.len() as u64;
+    let bundle_price = bundle.price;
+    let bundle = &mut ctx.accounts.bundle;
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    
+    bundle.purchases = bundle.purchases.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    marketplace_config.total_purchases = marketplace_config.total_purchases.checked_add(dataset_count).ok_or(DataChainError::InvalidParameters)?;
+    marketplace_config.total_volume = marketplace_config.total_volume.checked_add(bundle_price).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(BundlePurchased {
+        bundle: bundle.key(),
+        buyer: ctx.accounts.buyer.key(),
+        price: bundle_price,
+        platform_fee: split.platform_fee,
+        staking_fee: split.staking_amount,
+        seller_amount: split.seller_amount,
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+    
+    msg!("Bundle purchased: {} by {}", bundle.name, ctx.accounts.buyer.key());
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn member_shares_follow_list_prices() {
+        // A discounted bundle pays members in proportion to their list prices
+        assert_eq!(pro_rata(6_000, &[2_000, 6_000]).unwrap(), vec![1_500, 4_500]);
+        
+        // The rounding dust of uneven prices goes to the first member
+        assert_eq!(pro_rata(1_000, &[3, 5, 11]).unwrap(), vec![159, 263, 578]);
+    }
+    
+    #[test]
+    fn free_members_get_no_share_or_dust() {
+        assert_eq!(pro_rata(7, &[0, 500, 500]).unwrap(), vec![0, 4, 3]);
+        assert_eq!(pro_rata(0, &[0, 500]).unwrap(), vec![0, 0]);
+    }
+    
+    #[test]
+    fn bundles_without_priced_members_cannot_be_split() {
+        assert!(pro_rata(100, &[]).is_err());
+        assert!(pro_rata(100, &[0, 0]).is_err());
+    }
+    
+    #[test]
+    fn member_prices_may_sum_past_u64() {
+        assert_eq!(pro_rata(u64::MAX, &[u64::MAX, u64::MAX]).unwrap(), vec![u64::MAX / 2 + 1, u64::MAX / 2]);
+    }
+}
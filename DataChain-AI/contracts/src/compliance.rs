@@ -0,0 +1,292 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, FreezeAccount, ThawAccount};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::data_token::{TokenInfo, MINT_AUTHORITY_SEED};
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED};
+use crate::events::*;
+
+// Constants for compliance controls
+pub const BLOCKLIST_VERSION: u8 = 1;
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+pub const MAX_BLOCKLIST_ENTRIES: usize = 200;
+
+// Compliance blocklist structure
+#[account]
+pub struct Blocklist {
+    // Token info whose freeze authority manages the blocklist
+    pub token_info: Pubkey,
+    
+    // Blocked wallets
+    pub wallets: Vec<Pubkey>,
+    
+    // Time information
+    pub updated_at: i64,
+    
+    // Bump of the blocklist PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl Blocklist {
+    pub fn is_blocked(&self, wallet: &Pubkey) -> bool {
+        self.wallets.contains(wallet)
+    }
+}
+
+// Context for changing the freeze authority
+#[derive(Accounts)]
+pub struct SetFreezeAuthority<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+// Context for freezing or thawing a token account
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    pub freeze_authority: Signer<'info>,
+    
+    #[account(
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.freeze_authority == freeze_authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: Program mint authority PDA, also the mint freeze authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key() @ DataChainError::InvalidParameters
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Context for creating the blocklist, managed by the freeze authority of the canonical DATA token
+#[derive(Accounts)]
+pub struct InitializeBlocklist<'info> {
+    #[account(mut)]
+    pub freeze_authority: Signer<'info>,
+    
+    #[account(
+        constraint = token_info.key() == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = token_info.freeze_authority == freeze_authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        init,
+        payer = freeze_authority,
+        space = 8 + std::mem::size_of::<Blocklist>() + (32 * MAX_BLOCKLIST_ENTRIES),
+        seeds = [BLOCKLIST_SEED],
+        bump
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for adding or removing blocklist entries
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    pub freeze_authority: Signer<'info>,
+    
+    #[account(
+        constraint = token_info.freeze_authority == freeze_authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        mut,
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == token_info.key() @ DataChainError::InvalidParameters
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of compliance functions
+pub fn set_freeze_authority(
+    ctx: Context<SetFreezeAuthority>,
+    new_freeze_authority: Pubkey,
+) -> Result<()> {
+    let token_info = &mut ctx.accounts.token_info;
+    
+    token_info.freeze_authority = new_freeze_authority;
+    
+    emit!(FreezeAuthoritySet {
+        token_info: token_info.key(),
+        freeze_authority: new_freeze_authority,
+    });
+    
+    msg!("Freeze authority set to {}", new_freeze_authority);
+    
+    Ok(())
+}
+
+pub fn freeze_account(
+    ctx: Context<FreezeTokenAccount>,
+) -> Result<()> {
+    let token_info = &ctx.accounts.token_info;
+    let token_info_key = token_info.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[token_info.mint_authority_bump],
+    ];
+    
+    let cpi_accounts = FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    
+    token_interface::freeze_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+    )?;
+    
+    emit!(TokenAccountFrozen {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+    });
+    
+    msg!("Token account frozen: {}", ctx.accounts.token_account.key());
+    
+    Ok(())
+}
+
+pub fn thaw_account(
+    ctx: Context<FreezeTokenAccount>,
+) -> Result<()> {
+    let token_info = &ctx.accounts.token_info;
+    let token_info_key = token_info.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[token_info.mint_authority_bump],
+    ];
+    
+    let cpi_accounts = ThawAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    
+    token_interface::thaw_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+    )?;
+    
+    emit!(TokenAccountThawed {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+    });
+    
+    msg!("Token account thawed: {}", ctx.accounts.token_account.key());
+    
+    Ok(())
+}
+
+pub fn initialize_blocklist(
+    ctx: Context<InitializeBlocklist>,
+) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    
+    blocklist.token_info = ctx.accounts.token_info.key();
+    blocklist.wallets = Vec::new();
+    blocklist.updated_at = ctx.accounts.clock.unix_timestamp;
+    blocklist.bump = *ctx.bumps.get("blocklist").ok_or(DataChainError::SystemError)?;
+    blocklist.version = BLOCKLIST_VERSION;
+    
+    emit!(BlocklistInitialized {
+        blocklist: blocklist.key(),
+        token_info: blocklist.token_info,
+    });
+    
+    msg!("Compliance blocklist initialized");
+    
+    Ok(())
+}
+
+pub fn add_to_blocklist(
+    ctx: Context<UpdateBlocklist>,
+    wallet: Pubkey,
+) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    
+    if blocklist.is_blocked(&wallet) {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if blocklist.wallets.len() >= MAX_BLOCKLIST_ENTRIES {
+        return err!(DataChainError::BlocklistFull);
+    }
+    
+    blocklist.wallets.push(wallet);
+    blocklist.updated_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(BlocklistWalletAdded {
+        wallet,
+    });
+    
+    msg!("Wallet added to blocklist: {}", wallet);
+    
+    Ok(())
+}
+
+pub fn remove_from_blocklist(
+    ctx: Context<UpdateBlocklist>,
+    wallet: Pubkey,
+) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    
+    let index = blocklist.wallets
+        .iter()
+        .position(|blocked| *blocked == wallet)
+        .ok_or(DataChainError::InvalidParameters)?;
+    
+    blocklist.wallets.swap_remove(index);
+    blocklist.updated_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(BlocklistWalletRemoved {
+        wallet,
+    });
+    
+    msg!("Wallet removed from blocklist: {}", wallet);
+    
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::data_marketplace::{self, Dataset};
+use crate::events::*;
+
+// Constants for dataset coupons
+pub const COUPON_VERSION: u8 = 1;
+pub const COUPON_SEED: &[u8] = b"coupon";
+pub const MAX_COUPON_CODE_LENGTH: usize = 32;
+pub const COUPON_SPACE: usize = 8 + std::mem::size_of::<Coupon>();
+
+// Coupon discount types
+pub enum DiscountType {
+    Percentage = 0,
+    Fixed = 1,
+}
+
+// Seller-defined discount on a dataset, redeemed by passing it into a purchase
+#[account]
+pub struct Coupon {
+    // Dataset the coupon discounts and the owner who created it
+    pub dataset: Pubkey,
+    pub owner: Pubkey,
+    
+    // Hash of the coupon code, used as a seed
+    pub code_hash: [u8; 32],
+    
+    // Discount type, with a percentage of the price or a fixed amount off
+    pub discount_type: u8,
+    pub discount_value: u64,
+    
+    // Redemption limits
+    pub max_redemptions: u32,
+    pub redemptions: u32,
+    pub expires_at: Option<i64>,
+    pub allowed_buyer: Option<Pubkey>,
+    
+    // Creation timestamp
+    pub created_at: i64,
+    
+    // Bump of the coupon PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl Coupon {
+    pub fn check_redeemable(&self, buyer: &Pubkey, now: i64) -> Result<()> {
+        if matches!(self.expires_at, Some(expires_at) if now >= expires_at) {
+            return err!(DataChainError::CouponExpired);
+        }
+        
+        if self.redemptions >= self.max_redemptions {
+            return err!(DataChainError::CouponExhausted);
+        }
+        
+        if matches!(self.allowed_buyer, Some(allowed_buyer) if allowed_buyer != *buyer) {
+            return err!(DataChainError::CouponNotAllowed);
+        }
+        
+        Ok(())
+    }
+    
+    // Price after the discount, never below zero
+    pub fn discounted_price(&self, price: u64) -> Result<u64> {
+        let discount = if self.discount_type == DiscountType::Percentage as u8 {
+            (price as u128)
+                .checked_mul(self.discount_value as u128)
+                .ok_or(DataChainError::InvalidParameters)?
+                .checked_div(100)
+                .ok_or(DataChainError::InvalidParameters)? as u64
+        } else {
+            self.discount_value
+        };
+        
+        Ok(price.saturating_sub(discount))
+    }
+}
+
+// Address of a dataset's coupon for a code
+pub fn find_coupon_address(dataset: &Pubkey, code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COUPON_SEED, dataset.as_ref(), &data_marketplace::dataset_name_hash(code)],
+        &crate::ID,
+    )
+}
+
+// Context for the owner creating a coupon
+#[derive(Accounts)]
+pub struct CreateCoupon<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        constraint = dataset.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    /// CHECK: Coupon PDA, created in the instruction
+    #[account(mut)]
+    pub coupon: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for the owner closing a coupon
+#[derive(Accounts)]
+pub struct CloseCoupon<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = owner,
+        constraint = coupon.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub coupon: Account<'info, Coupon>,
+}
+
+// Implementation of coupon functions
+pub fn create_coupon(
+    ctx: Context<CreateCoupon>,
+    code: String,
+    discount_type: u8,
+    discount_value: u64,
+    max_redemptions: u32,
+    expires_at: Option<i64>,
+    allowed_buyer: Option<Pubkey>,
+) -> Result<()> {
+    let clock = &ctx.accounts.clock;
+    
+    // Validate input parameters
+    if code.is_empty() || code.len() > MAX_COUPON_CODE_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if discount_type == DiscountType::Percentage as u8 {
+        if discount_value == 0 || discount_value > 100 {
+            return err!(DataChainError::InvalidParameters);
+        }
+    } else if discount_type == DiscountType::Fixed as u8 {
+        if discount_value == 0 {
+            return err!(DataChainError::InvalidParameters);
+        }
+    } else {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if max_redemptions == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if matches!(expires_at, Some(expires_at) if expires_at <= clock.unix_timestamp) {
+        return err!(DataChainError::InvalidTimestamp);
+    }
+    
+    let owner = &ctx.accounts.owner;
+    let coupon_info = &ctx.accounts.coupon;
+    let dataset_key = ctx.accounts.dataset.key();
+    
+    // The coupon address is derived from the dataset and code
+    let code_hash = data_marketplace::dataset_name_hash(&code);
+    let (coupon_address, coupon_bump) = find_coupon_address(&dataset_key, &code);
+    if coupon_info.key() != coupon_address {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if *coupon_info.owner == crate::ID || !coupon_info.data_is_empty() {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let seeds = &[
+        COUPON_SEED,
+        dataset_key.as_ref(),
+        &code_hash,
+        &[coupon_bump],
+    ];
+    
+    data_marketplace::create_pda_account(
+        &owner.to_account_info(),
+        coupon_info,
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        COUPON_SPACE,
+        &seeds[..],
+    )?;
+    
+    let coupon = Coupon {
+        dataset: dataset_key,
+        owner: owner.key(),
+        code_hash,
+        discount_type,
+        discount_value,
+        max_redemptions,
+        redemptions: 0,
+        expires_at,
+        allowed_buyer,
+        created_at: clock.unix_timestamp,
+        bump: coupon_bump,
+        version: COUPON_VERSION,
+    };
+    
+    data_marketplace::write_account(coupon_info, &coupon)?;
+    
+    emit!(CouponCreated {
+        coupon: coupon_info.key(),
+        dataset: dataset_key,
+        discount_type,
+        discount_value,
+        max_redemptions,
+        expires_at,
+        allowed_buyer,
+    });
+    
+    msg!("Coupon created for dataset {}", dataset_key);
+    
+    Ok(())
+}
+
+pub fn close_coupon(
+    ctx: Context<CloseCoupon>,
+) -> Result<()> {
+    let coupon = &ctx.accounts.coupon;
+    
+    emit!(CouponClosed {
+        coupon: coupon.key(),
+        dataset: coupon.dataset,
+        redemptions: coupon.redemptions,
+    });
+    
+    msg!("Coupon closed after {} redemptions", coupon.redemptions);
+    
+    Ok(())
+}
@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use solana_program::{
-    program::invoke_signed,
+    hash::hash,
+    program::{invoke, invoke_signed},
+    system_instruction,
     sysvar::clock::Clock,
 };
 use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_DATASET_PURCHASES};
+use crate::staking::StakePool;
+use crate::data_token;
+use crate::compliance::{Blocklist, BLOCKLIST_SEED};
+use crate::coupon::Coupon;
+use crate::revenue_split::{RevenueSplit, REVENUE_SPLIT_SEED};
+use crate::escrow::{Escrow, EscrowStatus, ESCROW_SEED, ESCROW_VAULT_SEED, ESCROW_SPACE};
+use crate::events::*;
 
 // Constants for the data marketplace
 pub const PLATFORM_FEE_PERCENTAGE: u8 = 2; // 2% platform fee
@@ -16,6 +26,34 @@ pub const MAX_DATASET_DESCRIPTION_LENGTH: usize = 500;
 pub const MAX_DATA_TYPE_LENGTH: usize = 50;
 pub const MAX_URI_LENGTH: usize = 200;
 pub const MAX_REVIEW_LENGTH: usize = 500;
+pub const MAX_CHANGELOG_LENGTH: usize = 500;
+pub const DATASET_SEED: &[u8] = b"dataset";
+pub const PURCHASE_SEED: &[u8] = b"purchase";
+pub const DATASET_RATING_SEED: &[u8] = b"dataset_rating";
+pub const DATASET_VERSION_SEED: &[u8] = b"dataset_version";
+pub const MAX_ENCRYPTED_KEY_LENGTH: usize = 128; // Sealed box of a 32 byte key with room for its nonce and tag
+pub const DATASET_SPACE: usize = 8 + std::mem::size_of::<Dataset>() + MAX_DATASET_NAME_LENGTH + MAX_DATASET_DESCRIPTION_LENGTH + MAX_DATA_TYPE_LENGTH + (2 * MAX_URI_LENGTH);
+pub const PURCHASE_RECORD_SPACE: usize = 8 + std::mem::size_of::<PurchaseRecord>() + MAX_ENCRYPTED_KEY_LENGTH;
+pub const DATASET_VERSION_SPACE: usize = 8 + std::mem::size_of::<DatasetVersion>() + MAX_URI_LENGTH + MAX_CHANGELOG_LENGTH;
+
+// Dataset pricing modes
+pub enum PricingMode {
+    OneTime = 0,
+    Subscription = 1,
+}
+
+// Dataset versions a purchase grants access to
+pub enum AccessScope {
+    SingleVersion = 0,
+    AllVersions = 1,
+}
+
+// Delivery status of a buyer's dataset decryption key
+pub enum DeliveryStatus {
+    AwaitingKey = 0,
+    Pending = 1,
+    Delivered = 2,
+}
 
 // Dataset structure
 #[account]
@@ -28,24 +66,149 @@ pub struct Dataset {
     pub description: String,
     pub data_type: String,
     
-    // Dataset access info
+    // Dataset access info; uri is public and must locate ciphertext only, keys are delivered per buyer
     pub price: u64,
     pub uri: String,
     pub preview_uri: String,
     
+    // Pricing mode; subscriptions charge the price once per period
+    pub pricing_mode: u8,
+    pub subscription_period: i64,
+    
     // Dataset statistics
     pub purchases: u64,
     pub rating_sum: u64,
     pub rating_count: u64,
     
+    // Listing status, only listed datasets can be purchased
+    pub is_listed: bool,
+    
+    // Wallet allowed to deliver decryption keys besides the owner
+    pub delivery_authority: Pubkey,
+    
+    // Latest published version; zero is the uri given at registration, which has no version account
+    pub latest_version: u32,
+    
+    // Access scope granted to new purchases
+    pub access_scope: u8,
+    
+    // Whether sale proceeds are divided by the dataset's revenue split
+    pub has_revenue_split: bool,
+    
     // Creation timestamp
     pub created_at: i64,
     pub updated_at: i64,
     
+    // Bump of the dataset PDA
+    pub bump: u8,
+    
     // Version for future upgrades
     pub version: u8,
 }
 
+// Published revision of a dataset
+#[account]
+pub struct DatasetVersion {
+    // The dataset revised
+    pub dataset: Pubkey,
+    
+    // Version number, starting at 1
+    pub version_number: u32,
+    
+    // Integrity information for the published data
+    pub content_hash: [u8; 32],
+    pub byte_size: u64,
+    pub schema_hash: [u8; 32],
+    
+    // Public location of the encrypted data and what changed
+    pub uri: String,
+    pub changelog: String,
+    
+    // Publication timestamp
+    pub published_at: i64,
+    
+    // Bump of the dataset version PDA
+    pub bump: u8,
+}
+
+// Address of a published dataset version
+pub fn find_dataset_version_address(dataset: &Pubkey, version_number: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DATASET_VERSION_SEED, dataset.as_ref(), &version_number.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+// Hash of a dataset name, used as a seed since names can exceed the seed length limit
+pub fn dataset_name_hash(name: &str) -> [u8; 32] {
+    hash(name.as_bytes()).to_bytes()
+}
+
+// Address of a buyer's purchase record for a dataset
+pub fn find_purchase_record_address(dataset: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PURCHASE_SEED, dataset.as_ref(), buyer.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Address of the dataset an owner registered under a name
+pub fn find_dataset_address(owner: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DATASET_SEED, owner.as_ref(), &dataset_name_hash(name)],
+        &crate::ID,
+    )
+}
+
+// Create a program-owned PDA, tolerating lamports sent to the address in advance
+pub(crate) fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_lamports = rent.minimum_balance(space);
+    let current_lamports = target.lamports();
+    
+    if current_lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, target.key, rent_lamports, space as u64, &crate::ID),
+            &[payer.clone(), target.clone(), system_program.clone()],
+            &[seeds],
+        ).map_err(Into::into);
+    }
+    
+    if current_lamports < rent_lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, target.key, rent_lamports - current_lamports),
+            &[payer.clone(), target.clone(), system_program.clone()],
+        )?;
+    }
+    
+    invoke_signed(
+        &system_instruction::allocate(target.key, space as u64),
+        &[target.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    
+    invoke_signed(
+        &system_instruction::assign(target.key, &crate::ID),
+        &[target.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    
+    Ok(())
+}
+
+// Serialize an account, discriminator included, into a freshly created PDA
+pub(crate) fn write_account<T: AccountSerialize>(target: &AccountInfo, account: &T) -> Result<()> {
+    let mut data = target.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    account.try_serialize(&mut writer)
+}
+
 // Purchase record structure
 #[account]
 pub struct PurchaseRecord {
@@ -63,6 +226,220 @@ pub struct PurchaseRecord {
     
     // Transaction details
     pub price_paid: u64,
+    
+    // End of subscription access, None for one-time purchases
+    pub expires_at: Option<i64>,
+    
+    // Access scope and the latest version when purchased, zero for the registration uri
+    pub access_scope: u8,
+    pub version: u32,
+    
+    // Buyer's x25519 public key the decryption key is sealed to
+    pub encryption_key: Option<[u8; 32]>,
+    
+    // Dataset decryption key sealed to the buyer's encryption key
+    pub delivery_status: u8,
+    pub encrypted_key: Vec<u8>,
+    pub delivered_at: i64,
+    
+    // Bump of the purchase record PDA
+    pub bump: u8,
+}
+
+// Access terms of a purchase record, kept in escrow so a refunded repurchase can restore them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PurchaseTerms {
+    pub purchased_at: i64,
+    pub price_paid: u64,
+    pub expires_at: Option<i64>,
+    pub access_scope: u8,
+    pub version: u32,
+}
+
+impl PurchaseRecord {
+    pub fn terms(&self) -> PurchaseTerms {
+        PurchaseTerms {
+            purchased_at: self.purchased_at,
+            price_paid: self.price_paid,
+            expires_at: self.expires_at,
+            access_scope: self.access_scope,
+            version: self.version,
+        }
+    }
+    
+    pub fn restore_terms(&mut self, terms: &PurchaseTerms) {
+        self.purchased_at = terms.purchased_at;
+        self.price_paid = terms.price_paid;
+        self.expires_at = terms.expires_at;
+        self.access_scope = terms.access_scope;
+        self.version = terms.version;
+    }
+    
+    pub fn is_active(&self, now: i64) -> bool {
+        !matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+    
+    // All-versions access covers the version purchased and every later one, not earlier ones
+    pub fn grants_version(&self, version_number: u32) -> bool {
+        if self.access_scope == AccessScope::AllVersions as u8 {
+            version_number >= self.version
+        } else {
+            self.version == version_number
+        }
+    }
+    
+    pub fn is_delivered(&self) -> bool {
+        self.delivery_status == DeliveryStatus::Delivered as u8
+    }
+}
+
+// Fee split of a marketplace payment
+pub struct PaymentSplit {
+    pub platform_fee: u64,
+    pub staking_amount: u64,
+    pub seller_amount: u64,
+}
+
+// Split an amount into the platform fee, the stakers' share of it and the seller's proceeds
+pub fn split_payment(amount: u64, platform_fee_percentage: u8, staking_fee_percentage: u8) -> Result<PaymentSplit> {
+    let platform_fee = (amount as u128)
+        .checked_mul(platform_fee_percentage as u128)
+        .ok_or(DataChainError::InvalidParameters)?
+        .checked_div(100)
+        .ok_or(DataChainError::InvalidParameters)? as u64;
+    
+    let seller_amount = amount.checked_sub(platform_fee).ok_or(DataChainError::InvalidParameters)?;
+    
+    let staking_amount = (platform_fee as u128)
+        .checked_mul(staking_fee_percentage as u128)
+        .ok_or(DataChainError::InvalidParameters)?
+        .checked_div(100)
+        .ok_or(DataChainError::InvalidParameters)? as u64;
+    
+    Ok(PaymentSplit {
+        platform_fee,
+        staking_amount,
+        seller_amount,
+    })
+}
+
+impl PaymentSplit {
+    // Amount that must arrive in escrow to later pay every share in full
+    pub fn gross_total(&self, payment_mint: &AccountInfo, revenue_split: Option<&RevenueSplit>) -> Result<u64> {
+        let platform_amount = self.platform_fee.checked_sub(self.staking_amount).ok_or(DataChainError::InvalidParameters)?;
+        
+        let staking_transfer_amount = data_token::gross_amount_for_net(payment_mint, self.staking_amount)?;
+        let platform_transfer_amount = data_token::gross_amount_for_net(payment_mint, platform_amount)?;
+        let owner_transfer_amount = match revenue_split {
+            Some(revenue_split) => {
+                let mut total: u64 = 0;
+                for share in revenue_split.shares(self.seller_amount)? {
+                    let share_transfer_amount = data_token::gross_amount_for_net(payment_mint, share)?;
+                    total = total.checked_add(share_transfer_amount).ok_or(DataChainError::InvalidParameters)?;
+                }
+                total
+            }
+            None => data_token::gross_amount_for_net(payment_mint, self.seller_amount)?,
+        };
+        
+        let total = staking_transfer_amount
+            .checked_add(platform_transfer_amount)
+            .and_then(|total| total.checked_add(owner_transfer_amount))
+            .ok_or(DataChainError::InvalidParameters)?;
+        
+        Ok(total)
+    }
+}
+
+// Accounts a dataset price is paid from and to
+pub(crate) struct PaymentAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub payer_token: &'a InterfaceAccount<'info, TokenAccount>,
+    pub owner_token: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub platform_fee_token: &'a InterfaceAccount<'info, TokenAccount>,
+    pub staking_reward_vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub payment_mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    
+    // Revenue split of the dataset, paid to the recipient token accounts in the same order
+    pub revenue_split: Option<&'a RevenueSplit>,
+    pub recipient_tokens: &'a [AccountInfo<'info>],
+    
+    // Seeds of a PDA payer, empty when the payer signs the transaction
+    pub signer_seeds: &'a [&'a [&'a [u8]]],
+}
+
+impl<'info> PaymentAccounts<'_, 'info> {
+    // Pay the stakers, the platform and the seller their shares of a split
+    pub fn pay(&self, split: &PaymentSplit) -> Result<()> {
+        self.pay_fees(split)?;
+        
+        // Transfer payment to dataset owner, or to each revenue split recipient
+        match self.revenue_split {
+            Some(revenue_split) => self.pay_recipients(revenue_split, split.seller_amount),
+            None => {
+                let owner_token = self.owner_token.ok_or(DataChainError::InvalidParameters)?;
+                self.transfer(&owner_token.to_account_info(), split.seller_amount)
+            }
+        }
+    }
+    
+    // Pay the stakers' and the platform's shares of a split
+    pub fn pay_fees(&self, split: &PaymentSplit) -> Result<()> {
+        let platform_amount = split.platform_fee.checked_sub(split.staking_amount).ok_or(DataChainError::InvalidParameters)?;
+        
+        // Transfer staking share to the stake pool reward vault
+        if split.staking_amount > 0 {
+            let staking_reward_vault = self.staking_reward_vault.ok_or(DataChainError::InvalidParameters)?;
+            self.transfer(&staking_reward_vault.to_account_info(), split.staking_amount)?;
+        }
+        
+        // Transfer platform fee
+        self.transfer(&self.platform_fee_token.to_account_info(), platform_amount)
+    }
+    
+    fn pay_recipients(&self, revenue_split: &RevenueSplit, amount: u64) -> Result<()> {
+        if self.recipient_tokens.len() != revenue_split.recipients.len() {
+            return err!(DataChainError::InvalidRevenueSplit);
+        }
+        
+        let shares = revenue_split.shares(amount)?;
+        for ((recipient, share), recipient_token_info) in revenue_split.recipients.iter().zip(shares).zip(self.recipient_tokens) {
+            let recipient_token = InterfaceAccount::<TokenAccount>::try_from(recipient_token_info)?;
+            if recipient_token.mint != self.payment_mint.key() || recipient_token.owner != recipient.wallet {
+                return err!(DataChainError::InvalidRevenueSplit);
+            }
+            
+            if share > 0 {
+                self.transfer(recipient_token_info, share)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    // Transfer grossed up so the recipient receives the full amount after any transfer fee
+    pub fn transfer(&self, to: &AccountInfo<'info>, net_amount: u64) -> Result<()> {
+        let payment_mint = self.payment_mint.to_account_info();
+        let amount = data_token::gross_amount_for_net(&payment_mint, net_amount)?;
+        
+        let cpi_accounts = TransferChecked {
+            from: self.payer_token.to_account_info(),
+            mint: payment_mint,
+            to: to.clone(),
+            authority: self.payer.clone(),
+        };
+        
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                self.signer_seeds,
+            ),
+            amount,
+            self.payment_mint.decimals,
+        )
+    }
 }
 
 // Rating structure
@@ -92,6 +469,16 @@ pub struct MarketplaceConfig {
     pub platform_fee_percentage: u8,
     pub platform_fee_recipient: Pubkey,
     
+    // Mint purchases are paid in
+    pub payment_mint: Pubkey,
+    
+    // Share of the platform fee routed to DATA stakers
+    pub staking_fee_percentage: u8,
+    pub staking_reward_vault: Pubkey,
+    
+    // Time dataset payments are held in escrow, zero pays sellers immediately
+    pub escrow_period: i64,
+    
     // Statistics
     pub total_datasets: u64,
     pub total_purchases: u64,
@@ -101,18 +488,21 @@ pub struct MarketplaceConfig {
     pub version: u8,
 }
 
+impl MarketplaceConfig {
+    pub fn split_payment(&self, amount: u64) -> Result<PaymentSplit> {
+        split_payment(amount, self.platform_fee_percentage, self.staking_fee_percentage)
+    }
+}
+
 // Context for registering a dataset
 #[derive(Accounts)]
 pub struct RegisterDataset<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
     
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + std::mem::size_of::<Dataset>() + MAX_DATASET_NAME_LENGTH + MAX_DATASET_DESCRIPTION_LENGTH + MAX_DATA_TYPE_LENGTH + (2 * MAX_URI_LENGTH),
-    )]
-    pub dataset: Account<'info, Dataset>,
+    /// CHECK: Dataset PDA, created in the instruction so collisions surface as DatasetAlreadyRegistered
+    #[account(mut)]
+    pub dataset: AccountInfo<'info>,
     
     #[account(mut)]
     pub marketplace_config: Account<'info, MarketplaceConfig>,
@@ -130,199 +520,654 @@ pub struct PurchaseDataset<'info> {
     
     #[account(
         mut,
-        constraint = dataset.owner != buyer.key() @ DataChainError::Unauthorized
+        constraint = dataset.owner != buyer.key() @ DataChainError::Unauthorized,
+        constraint = dataset.is_listed @ DataChainError::DatasetNotListed,
+        constraint = dataset.has_revenue_split == revenue_split.is_some() @ DataChainError::InvalidRevenueSplit
     )]
     pub dataset: Account<'info, Dataset>,
     
-    #[account(mut)]
+    /// CHECK: Dataset owner, checked against the dataset
+    #[account(
+        constraint = dataset_owner.key() == dataset.owner @ DataChainError::Unauthorized
+    )]
     pub dataset_owner: AccountInfo<'info>,
     
+    /// CHECK: Purchase record PDA, created in the instruction so repeats surface as AccessAlreadyPurchased
     #[account(
-        init,
-        payer = buyer,
-        space = 8 + std::mem::size_of::<PurchaseRecord>(),
+        mut,
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), buyer.key().as_ref()],
+        bump
     )]
-    pub purchase_record: Account<'info, PurchaseRecord>,
+    pub purchase_record: AccountInfo<'info>,
+    
+    #[account(
+        constraint = payment_mint.key() == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
+        constraint = buyer_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = buyer_token.owner == buyer.key() @ DataChainError::Unauthorized
     )]
-    pub buyer_token: Account<'info, TokenAccount>,
+    pub buyer_token: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
+        constraint = owner_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = owner_token.owner == dataset_owner.key() @ DataChainError::Unauthorized
     )]
-    pub owner_token: Account<'info, TokenAccount>,
+    pub owner_token: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
+        constraint = platform_fee_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
         constraint = platform_fee_token.owner == marketplace_config.platform_fee_recipient @ DataChainError::Unauthorized
     )]
-    pub platform_fee_token: Account<'info, TokenAccount>,
+    pub platform_fee_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_reward_vault.key() == marketplace_config.staking_reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub staking_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [REVENUE_SPLIT_SEED, dataset.key().as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Account<'info, RevenueSplit>>,
     
     #[account(mut)]
     pub marketplace_config: Account<'info, MarketplaceConfig>,
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-    pub clock: Sysvar<'info, Clock>,
-}
-
-// Context for rating a dataset
-#[derive(Accounts)]
-pub struct RateDataset<'info> {
-    #[account(mut)]
-    pub rater: Signer<'info>,
+    #[account(
+        mut,
+        constraint = coupon.dataset == dataset.key() @ DataChainError::InvalidParameters
+    )]
+    pub coupon: Option<Account<'info, Coupon>>,
     
-    #[account(mut)]
-    pub dataset: Account<'info, Dataset>,
+    /// CHECK: Escrow PDA, created in the instruction when the marketplace escrows payments
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, purchase_record.key().as_ref()],
+        bump
+    )]
+    pub escrow: Option<AccountInfo<'info>>,
     
     #[account(
         mut,
-        constraint = purchase_record.buyer == rater.key() @ DataChainError::Unauthorized,
-        constraint = purchase_record.dataset == dataset.key() @ DataChainError::AccessNotPurchased,
-        constraint = !purchase_record.has_rated @ DataChainError::AlreadyRated
+        seeds = [ESCROW_VAULT_SEED, marketplace_config.key().as_ref(), payment_mint.key().as_ref()],
+        bump
     )]
-    pub purchase_record: Account<'info, PurchaseRecord>,
+    pub escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
-        init,
-        payer = rater,
-        space = 8 + std::mem::size_of::<DatasetRating>() + MAX_REVIEW_LENGTH,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_DATASET_PURCHASES) @ DataChainError::ProtocolPaused
     )]
-    pub rating: Account<'info, DatasetRating>,
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&buyer.key()) @ DataChainError::WalletBlocked,
+        constraint = !blocklist.is_blocked(&dataset.owner) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
     
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub clock: Sysvar<'info, Clock>,
 }
 
-// Implementation of marketplace functions
-pub fn register_dataset(
-    ctx: Context<RegisterDataset>,
-    name: String,
-    description: String,
-    data_type: String,
-    price: u64,
-    uri: String,
-    preview_uri: String,
-) -> Result<()> {
-    // Validate input parameters
-    if name.len() > MAX_DATASET_NAME_LENGTH {
-        return err!(DataChainError::InvalidParameters);
+impl<'info> PurchaseDataset<'info> {
+    fn payment_accounts<'a>(&'a self, recipient_tokens: &'a [AccountInfo<'info>]) -> PaymentAccounts<'a, 'info> {
+        PaymentAccounts {
+            payer: &self.buyer,
+            payer_token: &self.buyer_token,
+            owner_token: Some(&self.owner_token),
+            platform_fee_token: &self.platform_fee_token,
+            staking_reward_vault: self.staking_reward_vault.as_ref(),
+            payment_mint: &self.payment_mint,
+            token_program: &self.token_program,
+            revenue_split: self.revenue_split.as_deref(),
+            recipient_tokens,
+            signer_seeds: &[],
+        }
     }
     
-    if description.len() > MAX_DATASET_DESCRIPTION_LENGTH {
-        return err!(DataChainError::InvalidParameters);
+    fn escrow_accounts<'a>(&'a self, escrow_bump: u8) -> Result<EscrowAccounts<'a, 'info>> {
+        Ok(EscrowAccounts {
+            escrow: self.escrow.as_ref().ok_or(DataChainError::InvalidParameters)?,
+            escrow_vault: self.escrow_vault.as_ref().ok_or(DataChainError::InvalidParameters)?,
+            escrow_bump,
+            marketplace_config: &self.marketplace_config,
+            dataset: &self.dataset,
+            purchase_record: self.purchase_record.key(),
+            system_program: &self.system_program,
+            rent: &self.rent,
+            clock: &self.clock,
+        })
     }
+}
+
+// Context for extending a dataset subscription
+#[derive(Accounts)]
+pub struct RenewSubscription<'info> {
+    pub buyer: Signer<'info>,
     
-    if data_type.len() > MAX_DATA_TYPE_LENGTH {
-        return err!(DataChainError::InvalidParameters);
-    }
+    #[account(
+        constraint = dataset.is_listed @ DataChainError::DatasetNotListed,
+        constraint = dataset.pricing_mode == PricingMode::Subscription as u8 @ DataChainError::InvalidPricingMode,
+        constraint = dataset.has_revenue_split == revenue_split.is_some() @ DataChainError::InvalidRevenueSplit
+    )]
+    pub dataset: Account<'info, Dataset>,
     
-    if uri.len() > MAX_URI_LENGTH || preview_uri.len() > MAX_URI_LENGTH {
-        return err!(DataChainError::InvalidParameters);
-    }
+    /// CHECK: Dataset owner, checked against the dataset
+    #[account(
+        constraint = dataset_owner.key() == dataset.owner @ DataChainError::Unauthorized
+    )]
+    pub dataset_owner: AccountInfo<'info>,
     
-    if price == 0 {
-        return err!(DataChainError::InvalidDatasetPrice);
-    }
+    #[account(
+        mut,
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase_record.bump,
+        constraint = purchase_record.expires_at.is_some() @ DataChainError::InvalidPricingMode
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
     
-    let dataset = &mut ctx.accounts.dataset;
-    let marketplace_config = &mut ctx.accounts.marketplace_config;
-    let clock = &ctx.accounts.clock;
+    #[account(
+        constraint = payment_mint.key() == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
     
-    // Initialize dataset
-    dataset.owner = ctx.accounts.owner.key();
-    dataset.name = name;
-    dataset.description = description;
-    dataset.data_type = data_type;
-    dataset.price = price;
-    dataset.uri = uri;
-    dataset.preview_uri = preview_uri;
-    dataset.purchases = 0;
-    dataset.rating_sum = 0;
-    dataset.rating_count = 0;
-    dataset.created_at = clock.unix_timestamp;
-    dataset.updated_at = clock.unix_timestamp;
-    dataset.version = MARKETPLACE_VERSION;
+    #[account(
+        mut,
+        constraint = buyer_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = buyer_token.owner == buyer.key() @ DataChainError::Unauthorized
+    )]
+    pub buyer_token: InterfaceAccount<'info, TokenAccount>,
     
-    // Update marketplace stats
-    marketplace_config.total_datasets = marketplace_config.total_datasets.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    #[account(
+        mut,
+        constraint = owner_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == dataset_owner.key() @ DataChainError::Unauthorized
+    )]
+    pub owner_token: InterfaceAccount<'info, TokenAccount>,
     
-    msg!("Dataset registered: {}", dataset.name);
+    #[account(
+        mut,
+        constraint = platform_fee_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = platform_fee_token.owner == marketplace_config.platform_fee_recipient @ DataChainError::Unauthorized
+    )]
+    pub platform_fee_token: InterfaceAccount<'info, TokenAccount>,
     
-    Ok(())
-}
-
-pub fn purchase_dataset(
-    ctx: Context<PurchaseDataset>,
-    dataset_id: Pubkey,
-) -> Result<()> {
-    let dataset = &mut ctx.accounts.dataset;
-    let buyer = &ctx.accounts.buyer;
-    let purchase_record = &mut ctx.accounts.purchase_record;
-    let marketplace_config = &mut ctx.accounts.marketplace_config;
-    let clock = &ctx.accounts.clock;
+    #[account(
+        mut,
+        constraint = staking_reward_vault.key() == marketplace_config.staking_reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub staking_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
     
-    // Check if the dataset exists and matches provided ID
-    if dataset.key() != dataset_id {
-        return err!(DataChainError::DatasetNotFound);
-    }
+    #[account(
+        seeds = [REVENUE_SPLIT_SEED, dataset.key().as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Account<'info, RevenueSplit>>,
     
-    // Calculate fees
-    let purchase_amount = dataset.price;
-    let platform_fee = (purchase_amount as u128)
-        .checked_mul(marketplace_config.platform_fee_percentage as u128)
-        .ok_or(DataChainError::InvalidParameters)?
-        .checked_div(100)
-        .ok_or(DataChainError::InvalidParameters)? as u64;
+    #[account(mut)]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
     
-    let seller_amount = purchase_amount.checked_sub(platform_fee).ok_or(DataChainError::InvalidParameters)?;
+    /// CHECK: Escrow PDA, created in the instruction when the marketplace escrows payments
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, purchase_record.key().as_ref()],
+        bump
+    )]
+    pub escrow: Option<AccountInfo<'info>>,
     
-    // Transfer platform fee
-    {
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.buyer_token.to_account_info(),
-            to: ctx.accounts.platform_fee_token.to_account_info(),
-            authority: buyer.to_account_info(),
-        };
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED, marketplace_config.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_DATASET_PURCHASES) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&buyer.key()) @ DataChainError::WalletBlocked,
+        constraint = !blocklist.is_blocked(&dataset.owner) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> RenewSubscription<'info> {
+    fn payment_accounts<'a>(&'a self, recipient_tokens: &'a [AccountInfo<'info>]) -> PaymentAccounts<'a, 'info> {
+        PaymentAccounts {
+            payer: &self.buyer,
+            payer_token: &self.buyer_token,
+            owner_token: Some(&self.owner_token),
+            platform_fee_token: &self.platform_fee_token,
+            staking_reward_vault: self.staking_reward_vault.as_ref(),
+            payment_mint: &self.payment_mint,
+            token_program: &self.token_program,
+            revenue_split: self.revenue_split.as_deref(),
+            recipient_tokens,
+            signer_seeds: &[],
+        }
+    }
+    
+    fn escrow_accounts<'a>(&'a self, escrow_bump: u8) -> Result<EscrowAccounts<'a, 'info>> {
+        Ok(EscrowAccounts {
+            escrow: self.escrow.as_ref().ok_or(DataChainError::InvalidParameters)?,
+            escrow_vault: self.escrow_vault.as_ref().ok_or(DataChainError::InvalidParameters)?,
+            escrow_bump,
+            marketplace_config: &self.marketplace_config,
+            dataset: &self.dataset,
+            purchase_record: self.purchase_record.key(),
+            system_program: &self.system_program,
+            rent: &self.rent,
+            clock: &self.clock,
+        })
+    }
+}
+
+// Context for the owner updating, repricing or delisting a dataset
+#[derive(Accounts)]
+pub struct UpdateDataset<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = dataset.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for a buyer setting the key their dataset decryption key is sealed to
+#[derive(Accounts)]
+pub struct SetEncryptionKey<'info> {
+    pub buyer: Signer<'info>,
+    
+    pub dataset: Account<'info, Dataset>,
+    
+    #[account(
+        mut,
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase_record.bump,
+        constraint = !purchase_record.is_delivered() @ DataChainError::AccessKeyAlreadyDelivered
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+}
+
+// Context for the seller or their delivery authority posting a buyer's decryption key
+#[derive(Accounts)]
+pub struct DeliverAccessKey<'info> {
+    pub delivery_authority: Signer<'info>,
+    
+    #[account(
+        constraint = dataset.owner == delivery_authority.key()
+            || dataset.delivery_authority == delivery_authority.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    #[account(
+        mut,
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), purchase_record.buyer.as_ref()],
+        bump = purchase_record.bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for rating a dataset
+#[derive(Accounts)]
+pub struct RateDataset<'info> {
+    #[account(mut)]
+    pub rater: Signer<'info>,
+    
+    #[account(mut)]
+    pub dataset: Account<'info, Dataset>,
+    
+    #[account(
+        mut,
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), rater.key().as_ref()],
+        bump = purchase_record.bump,
+        constraint = !purchase_record.has_rated @ DataChainError::AlreadyRated
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+    
+    #[account(
+        init,
+        payer = rater,
+        space = 8 + std::mem::size_of::<DatasetRating>() + MAX_REVIEW_LENGTH,
+        seeds = [DATASET_RATING_SEED, dataset.key().as_ref(), rater.key().as_ref()],
+        bump
+    )]
+    pub rating: Account<'info, DatasetRating>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for checking a buyer's access to a dataset
+#[derive(Accounts)]
+pub struct VerifyAccess<'info> {
+    pub dataset: Account<'info, Dataset>,
+    
+    /// CHECK: Wallet whose access is checked
+    pub buyer: AccountInfo<'info>,
+    
+    /// CHECK: Purchase record PDA, possibly not created yet
+    #[account(
+        seeds = [PURCHASE_SEED, dataset.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: AccountInfo<'info>,
+    
+    #[account(
+        constraint = dataset_version.dataset == dataset.key() @ DataChainError::InvalidParameters
+    )]
+    pub dataset_version: Option<Account<'info, DatasetVersion>>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for the owner publishing a new dataset version
+#[derive(Accounts)]
+pub struct PublishDatasetVersion<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = dataset.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = DATASET_VERSION_SPACE,
+        seeds = [DATASET_VERSION_SEED, dataset.key().as_ref(), &(dataset.latest_version + 1).to_le_bytes()],
+        bump
+    )]
+    pub dataset_version: Account<'info, DatasetVersion>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of marketplace functions
+
+// The uri is readable by anyone, so it must point to the encrypted data; buyers get the key by delivery
+pub fn register_dataset(
+    ctx: Context<RegisterDataset>,
+    name: String,
+    description: String,
+    data_type: String,
+    price: u64,
+    uri: String,
+    preview_uri: String,
+) -> Result<()> {
+    // Validate input parameters
+    if name.len() > MAX_DATASET_NAME_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if description.len() > MAX_DATASET_DESCRIPTION_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if data_type.len() > MAX_DATA_TYPE_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if uri.len() > MAX_URI_LENGTH || preview_uri.len() > MAX_URI_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if price == 0 {
+        return err!(DataChainError::InvalidDatasetPrice);
+    }
+    
+    let owner = &ctx.accounts.owner;
+    let dataset_info = &ctx.accounts.dataset;
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    let clock = &ctx.accounts.clock;
+    
+    // The dataset address is derived from the owner and name
+    let name_hash = dataset_name_hash(&name);
+    let (dataset_address, dataset_bump) = find_dataset_address(&owner.key(), &name);
+    if dataset_info.key() != dataset_address {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if *dataset_info.owner == crate::ID || !dataset_info.data_is_empty() {
+        return err!(DataChainError::DatasetAlreadyRegistered);
+    }
+    
+    // Create the dataset account
+    let seeds = &[
+        DATASET_SEED,
+        owner.key.as_ref(),
+        &name_hash,
+        &[dataset_bump],
+    ];
+    
+    create_pda_account(
+        &owner.to_account_info(),
+        dataset_info,
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        DATASET_SPACE,
+        &seeds[..],
+    )?;
+    
+    // Initialize dataset
+    let dataset = Dataset {
+        owner: owner.key(),
+        name,
+        description,
+        data_type,
+        price,
+        uri,
+        preview_uri,
+        pricing_mode: PricingMode::OneTime as u8,
+        subscription_period: 0,
+        purchases: 0,
+        rating_sum: 0,
+        rating_count: 0,
+        is_listed: true,
+        delivery_authority: owner.key(),
+        latest_version: 0,
+        access_scope: AccessScope::AllVersions as u8,
+        has_revenue_split: false,
+        created_at: clock.unix_timestamp,
+        updated_at: clock.unix_timestamp,
+        bump: dataset_bump,
+        version: MARKETPLACE_VERSION,
+    };
+    
+    write_account(dataset_info, &dataset)?;
+    
+    // Update marketplace stats
+    marketplace_config.total_datasets = marketplace_config.total_datasets.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(DatasetRegistered {
+        dataset: dataset_info.key(),
+        owner: dataset.owner,
+        price: dataset.price,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset registered: {}", dataset.name);
+    
+    Ok(())
+}
+
+pub fn purchase_dataset<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseDataset<'info>>,
+    dataset_id: Pubkey,
+) -> Result<()> {
+    if ctx.accounts.dataset.pricing_mode != PricingMode::OneTime as u8 {
+        return err!(DataChainError::InvalidPricingMode);
+    }
+    
+    process_purchase(ctx, dataset_id, None)
+}
+
+pub fn subscribe_dataset<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseDataset<'info>>,
+    dataset_id: Pubkey,
+) -> Result<()> {
+    let dataset = &ctx.accounts.dataset;
+    
+    if dataset.pricing_mode != PricingMode::Subscription as u8 {
+        return err!(DataChainError::InvalidPricingMode);
+    }
+    
+    let expires_at = ctx.accounts.clock.unix_timestamp
+        .checked_add(dataset.subscription_period)
+        .ok_or(DataChainError::InvalidTimestamp)?;
+    
+    process_purchase(ctx, dataset_id, Some(expires_at))
+}
+
+// Pay for a dataset and write the buyer's purchase record; lapsed access can be bought again
+fn process_purchase<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseDataset<'info>>,
+    dataset_id: Pubkey,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    let clock = &ctx.accounts.clock;
+    
+    // Check if the dataset exists and matches provided ID
+    if ctx.accounts.dataset.key() != dataset_id {
+        return err!(DataChainError::DatasetNotFound);
+    }
+    
+    // One active purchase per buyer per dataset
+    let purchase_record_info = &ctx.accounts.purchase_record;
+    let existing_record = if purchase_record_info.data_is_empty() {
+        None
+    } else {
+        Some(Account::<PurchaseRecord>::try_from(purchase_record_info)?.into_inner())
+    };
+    
+    // Single version buyers can buy again once a newer version is published
+    let latest_version = ctx.accounts.dataset.latest_version;
+    if existing_record.as_ref().is_some_and(|record| record.is_active(clock.unix_timestamp) && record.grants_version(latest_version)) {
+        return err!(DataChainError::AccessAlreadyPurchased);
+    }
+    
+    // Apply the coupon, if any; the discounted amount is what the buyer pays and what the record shows
+    let mut purchase_amount = ctx.accounts.dataset.price;
+    if let Some(coupon) = &mut ctx.accounts.coupon {
+        coupon.check_redeemable(&ctx.accounts.buyer.key(), clock.unix_timestamp)?;
         
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-            ),
-            platform_fee,
-        )?;
+        let list_price = purchase_amount;
+        purchase_amount = coupon.discounted_price(list_price)?;
+        coupon.redemptions = coupon.redemptions.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+        
+        emit!(CouponRedeemed {
+            coupon: coupon.key(),
+            dataset: coupon.dataset,
+            buyer: ctx.accounts.buyer.key(),
+            list_price,
+            price_paid: purchase_amount,
+            redemptions: coupon.redemptions,
+        });
     }
     
-    // Transfer payment to dataset owner
-    {
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.buyer_token.to_account_info(),
-            to: ctx.accounts.owner_token.to_account_info(),
-            authority: buyer.to_account_info(),
-        };
+    let split = if ctx.accounts.marketplace_config.escrow_period > 0 {
+        let escrow_bump = *ctx.bumps.get("escrow").ok_or(DataChainError::SystemError)?;
+        let coupon = ctx.accounts.coupon.as_ref().map(|coupon| coupon.key());
+        let prior_terms = existing_record.as_ref().map(PurchaseRecord::terms);
+        ctx.accounts.escrow_accounts(escrow_bump)?.hold(&ctx.accounts.payment_accounts(&[]), purchase_amount, 0, prior_terms, coupon)?
+    } else {
+        let split = ctx.accounts.marketplace_config.split_payment(purchase_amount)?;
+        ctx.accounts.payment_accounts(ctx.remaining_accounts).pay(&split)?;
+        split
+    };
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let buyer = &ctx.accounts.buyer;
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    
+    // Create purchase record
+    let dataset_key = dataset.key();
+    let purchase_record_bump = *ctx.bumps.get("purchase_record").ok_or(DataChainError::SystemError)?;
+    
+    if existing_record.is_none() {
+        let seeds = &[
+            PURCHASE_SEED,
+            dataset_key.as_ref(),
+            buyer.key.as_ref(),
+            &[purchase_record_bump],
+        ];
         
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-            ),
-            seller_amount,
+        create_pda_account(
+            &buyer.to_account_info(),
+            purchase_record_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.rent,
+            PURCHASE_RECORD_SPACE,
+            &seeds[..],
         )?;
     }
     
-    // Create purchase record
-    purchase_record.buyer = buyer.key();
-    purchase_record.dataset = dataset.key();
-    purchase_record.purchased_at = clock.unix_timestamp;
-    purchase_record.has_rated = false;
-    purchase_record.price_paid = purchase_amount;
+    // Rating and key delivery carry over when lapsed access is bought again
+    let purchase_record = match existing_record {
+        Some(record) => PurchaseRecord {
+            purchased_at: clock.unix_timestamp,
+            price_paid: purchase_amount,
+            expires_at,
+            access_scope: dataset.access_scope,
+            version: latest_version,
+            ..record
+        },
+        None => PurchaseRecord {
+            buyer: buyer.key(),
+            dataset: dataset_key,
+            purchased_at: clock.unix_timestamp,
+            has_rated: false,
+            price_paid: purchase_amount,
+            expires_at,
+            access_scope: dataset.access_scope,
+            version: latest_version,
+            encryption_key: None,
+            delivery_status: DeliveryStatus::AwaitingKey as u8,
+            encrypted_key: Vec::new(),
+            delivered_at: 0,
+            bump: purchase_record_bump,
+        },
+    };
+    
+    write_account(purchase_record_info, &purchase_record)?;
     
     // Update dataset stats
     dataset.purchases = dataset.purchases.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
@@ -331,7 +1176,176 @@ pub fn purchase_dataset(
     marketplace_config.total_purchases = marketplace_config.total_purchases.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     marketplace_config.total_volume = marketplace_config.total_volume.checked_add(purchase_amount).ok_or(DataChainError::InvalidParameters)?;
     
-    msg!("Dataset purchased: {} by {}", dataset.name, buyer.key());
+    match expires_at {
+        None => {
+            emit!(DatasetPurchased {
+                dataset: dataset_key,
+                buyer: buyer.key(),
+                seller: dataset.owner,
+                price: purchase_amount,
+                platform_fee: split.platform_fee,
+                staking_fee: split.staking_amount,
+                seller_amount: split.seller_amount,
+                timestamp: clock.unix_timestamp,
+            });
+            
+            msg!("Dataset purchased: {} by {}", dataset.name, buyer.key());
+        }
+        Some(expires_at) => {
+            emit!(DatasetSubscribed {
+                dataset: dataset_key,
+                buyer: buyer.key(),
+                seller: dataset.owner,
+                price: purchase_amount,
+                platform_fee: split.platform_fee,
+                staking_fee: split.staking_amount,
+                seller_amount: split.seller_amount,
+                expires_at,
+                timestamp: clock.unix_timestamp,
+            });
+            
+            msg!("Dataset subscribed: {} by {} until {}", dataset.name, buyer.key(), expires_at);
+        }
+    }
+    
+    Ok(())
+}
+
+// Accounts a purchase or renewal payment is held in escrow with
+struct EscrowAccounts<'a, 'info> {
+    escrow: &'a AccountInfo<'info>,
+    escrow_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    escrow_bump: u8,
+    marketplace_config: &'a Account<'info, MarketplaceConfig>,
+    dataset: &'a Account<'info, Dataset>,
+    purchase_record: Pubkey,
+    system_program: &'a Program<'info, System>,
+    rent: &'a Sysvar<'info, Rent>,
+    clock: &'a Sysvar<'info, Clock>,
+}
+
+impl<'info> EscrowAccounts<'_, 'info> {
+    // Move the buyer's payment into the escrow vault and record the shares owed on release
+    fn hold(
+        &self,
+        payment: &PaymentAccounts<'_, 'info>,
+        purchase_amount: u64,
+        renewal_period: i64,
+        prior_terms: Option<PurchaseTerms>,
+        coupon: Option<Pubkey>,
+    ) -> Result<PaymentSplit> {
+        let escrow_info = self.escrow;
+        let clock = self.clock;
+        
+        // A lapsed purchase bought again, or a renewal, must have the earlier escrow settled first
+        if *escrow_info.owner == crate::ID || !escrow_info.data_is_empty() {
+            return err!(DataChainError::InvalidEscrowStatus);
+        }
+        
+        let split = self.marketplace_config.split_payment(purchase_amount)?;
+        let amount = split.gross_total(&payment.payment_mint.to_account_info(), payment.revenue_split)?;
+        payment.transfer(&self.escrow_vault.to_account_info(), amount)?;
+        
+        let seeds = &[
+            ESCROW_SEED,
+            self.purchase_record.as_ref(),
+            &[self.escrow_bump],
+        ];
+        
+        create_pda_account(
+            payment.payer,
+            escrow_info,
+            &self.system_program.to_account_info(),
+            self.rent,
+            ESCROW_SPACE,
+            &seeds[..],
+        )?;
+        
+        let release_at = clock.unix_timestamp
+            .checked_add(self.marketplace_config.escrow_period)
+            .ok_or(DataChainError::InvalidTimestamp)?;
+        let escrow = Escrow {
+            marketplace_config: self.marketplace_config.key(),
+            purchase_record: self.purchase_record,
+            dataset: self.dataset.key(),
+            buyer: payment.payer.key(),
+            seller: self.dataset.owner,
+            payment_mint: payment.payment_mint.key(),
+            amount,
+            platform_fee: split.platform_fee,
+            staking_amount: split.staking_amount,
+            seller_amount: split.seller_amount,
+            split_recipients: payment.revenue_split.map(|revenue_split| revenue_split.recipients.clone()).unwrap_or_default(),
+            renewal_period,
+            prior_terms,
+            coupon,
+            status: EscrowStatus::Held as u8,
+            release_at,
+            created_at: clock.unix_timestamp,
+            bump: self.escrow_bump,
+        };
+        
+        write_account(escrow_info, &escrow)?;
+        
+        emit!(PaymentEscrowed {
+            escrow: escrow_info.key(),
+            dataset: escrow.dataset,
+            buyer: escrow.buyer,
+            amount,
+            release_at,
+        });
+        
+        Ok(split)
+    }
+}
+
+// Extends a subscription by one period from its expiry, or from now if it has lapsed
+pub fn renew_subscription<'info>(
+    ctx: Context<'_, '_, '_, 'info, RenewSubscription<'info>>,
+) -> Result<()> {
+    let purchase_amount = ctx.accounts.dataset.price;
+    let subscription_period = ctx.accounts.dataset.subscription_period;
+    
+    // Renewals are escrowed like first purchases, so a refund can take back the renewed period
+    let split = if ctx.accounts.marketplace_config.escrow_period > 0 {
+        let escrow_bump = *ctx.bumps.get("escrow").ok_or(DataChainError::SystemError)?;
+        ctx.accounts.escrow_accounts(escrow_bump)?.hold(&ctx.accounts.payment_accounts(&[]), purchase_amount, subscription_period, None, None)?
+    } else {
+        let split = ctx.accounts.marketplace_config.split_payment(purchase_amount)?;
+        ctx.accounts.payment_accounts(ctx.remaining_accounts).pay(&split)?;
+        split
+    };
+    
+    let dataset = &ctx.accounts.dataset;
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    let clock = &ctx.accounts.clock;
+    
+    let current_expiry = purchase_record.expires_at.ok_or(DataChainError::InvalidPricingMode)?;
+    let expires_at = current_expiry
+        .max(clock.unix_timestamp)
+        .checked_add(subscription_period)
+        .ok_or(DataChainError::InvalidTimestamp)?;
+    
+    purchase_record.expires_at = Some(expires_at);
+    purchase_record.price_paid = purchase_record.price_paid.checked_add(purchase_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    // Update marketplace stats
+    marketplace_config.total_volume = marketplace_config.total_volume.checked_add(purchase_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(SubscriptionRenewed {
+        dataset: dataset.key(),
+        buyer: purchase_record.buyer,
+        seller: dataset.owner,
+        price: purchase_amount,
+        platform_fee: split.platform_fee,
+        staking_fee: split.staking_amount,
+        seller_amount: split.seller_amount,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Subscription renewed: {} by {} until {}", dataset.name, purchase_record.buyer, expires_at);
     
     Ok(())
 }
@@ -376,11 +1390,347 @@ pub fn rate_dataset(
     dataset.rating_sum = dataset.rating_sum.checked_add(rating_value as u64).ok_or(DataChainError::InvalidParameters)?;
     dataset.rating_count = dataset.rating_count.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(DatasetRated {
+        dataset: dataset.key(),
+        rater: rating.rater,
+        rating: rating_value,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("Dataset rated: {} with rating {}", dataset.name, rating_value);
     
     Ok(())
 }
 
+// Succeeds only if the buyer holds an unexpired purchase record for the dataset; meant to be simulated by gateways
+pub fn verify_access(
+    ctx: Context<VerifyAccess>,
+) -> Result<()> {
+    let purchase_record_info = &ctx.accounts.purchase_record;
+    
+    if *purchase_record_info.owner != crate::ID || purchase_record_info.data_is_empty() {
+        return err!(DataChainError::AccessNotPurchased);
+    }
+    
+    let purchase_record = Account::<PurchaseRecord>::try_from(purchase_record_info)?;
+    if !purchase_record.is_active(ctx.accounts.clock.unix_timestamp) {
+        return err!(DataChainError::SubscriptionExpired);
+    }
+    
+    // Checks a specific version when one is passed
+    if let Some(dataset_version) = &ctx.accounts.dataset_version {
+        if !purchase_record.grants_version(dataset_version.version_number) {
+            return err!(DataChainError::VersionNotPurchased);
+        }
+    }
+    
+    msg!("Access verified: {} for {}", ctx.accounts.buyer.key(), ctx.accounts.dataset.key());
+    
+    Ok(())
+}
+
+// Setting a new key invalidates any key already delivered, so the seller must deliver again
+// Can be changed until the key is delivered, so a delivered purchase cannot be reset to pending
+pub fn set_encryption_key(
+    ctx: Context<SetEncryptionKey>,
+    encryption_key: [u8; 32],
+) -> Result<()> {
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    
+    purchase_record.encryption_key = Some(encryption_key);
+    purchase_record.delivery_status = DeliveryStatus::Pending as u8;
+    purchase_record.encrypted_key = Vec::new();
+    purchase_record.delivered_at = 0;
+    
+    emit!(EncryptionKeySet {
+        purchase_record: purchase_record.key(),
+        dataset: purchase_record.dataset,
+        buyer: purchase_record.buyer,
+        encryption_key,
+    });
+    
+    msg!("Encryption key set for {} on {}", purchase_record.buyer, purchase_record.dataset);
+    
+    Ok(())
+}
+
+pub fn deliver_access_key(
+    ctx: Context<DeliverAccessKey>,
+    encrypted_key: Vec<u8>,
+) -> Result<()> {
+    if encrypted_key.is_empty() || encrypted_key.len() > MAX_ENCRYPTED_KEY_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    let clock = &ctx.accounts.clock;
+    
+    if purchase_record.encryption_key.is_none() {
+        return err!(DataChainError::EncryptionKeyNotSet);
+    }
+    
+    purchase_record.delivery_status = DeliveryStatus::Delivered as u8;
+    purchase_record.encrypted_key = encrypted_key;
+    purchase_record.delivered_at = clock.unix_timestamp;
+    
+    emit!(AccessKeyDelivered {
+        purchase_record: purchase_record.key(),
+        dataset: purchase_record.dataset,
+        buyer: purchase_record.buyer,
+        delivered_by: ctx.accounts.delivery_authority.key(),
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Access key delivered to {} for {}", purchase_record.buyer, purchase_record.dataset);
+    
+    Ok(())
+}
+
+// The new version's uri becomes the dataset uri, and like it must point to encrypted data
+pub fn publish_dataset_version(
+    ctx: Context<PublishDatasetVersion>,
+    content_hash: [u8; 32],
+    byte_size: u64,
+    schema_hash: [u8; 32],
+    uri: String,
+    changelog: String,
+) -> Result<()> {
+    // Validate input parameters
+    if uri.len() > MAX_URI_LENGTH || changelog.len() > MAX_CHANGELOG_LENGTH {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if byte_size == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let dataset_version = &mut ctx.accounts.dataset_version;
+    let clock = &ctx.accounts.clock;
+    
+    let version_number = dataset.latest_version.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    
+    dataset_version.dataset = dataset.key();
+    dataset_version.version_number = version_number;
+    dataset_version.content_hash = content_hash;
+    dataset_version.byte_size = byte_size;
+    dataset_version.schema_hash = schema_hash;
+    dataset_version.uri = uri.clone();
+    dataset_version.changelog = changelog;
+    dataset_version.published_at = clock.unix_timestamp;
+    dataset_version.bump = *ctx.bumps.get("dataset_version").ok_or(DataChainError::SystemError)?;
+    
+    dataset.latest_version = version_number;
+    dataset.uri = uri;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetVersionPublished {
+        dataset: dataset.key(),
+        dataset_version: dataset_version.key(),
+        version_number,
+        content_hash,
+        byte_size,
+        schema_hash,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset version published: {} v{}", dataset.name, version_number);
+    
+    Ok(())
+}
+
+// Existing purchases keep the scope they were bought with
+pub fn set_dataset_access_scope(
+    ctx: Context<UpdateDataset>,
+    access_scope: u8,
+) -> Result<()> {
+    if access_scope != AccessScope::SingleVersion as u8 && access_scope != AccessScope::AllVersions as u8 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    dataset.access_scope = access_scope;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetAccessScopeSet {
+        dataset: dataset.key(),
+        access_scope,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset access scope set: {} to {}", dataset.name, access_scope);
+    
+    Ok(())
+}
+
+// The content uri only changes by publishing a new version
+pub fn update_dataset(
+    ctx: Context<UpdateDataset>,
+    description: Option<String>,
+    preview_uri: Option<String>,
+) -> Result<()> {
+    // Validate input parameters
+    if let Some(description_text) = &description {
+        if description_text.len() > MAX_DATASET_DESCRIPTION_LENGTH {
+            return err!(DataChainError::InvalidParameters);
+        }
+    }
+    
+    if let Some(preview_uri_text) = &preview_uri {
+        if preview_uri_text.len() > MAX_URI_LENGTH {
+            return err!(DataChainError::InvalidParameters);
+        }
+    }
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    // Update only the provided fields
+    if let Some(description_text) = description {
+        dataset.description = description_text;
+    }
+    
+    if let Some(preview_uri_text) = preview_uri {
+        dataset.preview_uri = preview_uri_text;
+    }
+    
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetUpdated {
+        dataset: dataset.key(),
+        owner: dataset.owner,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset updated: {}", dataset.name);
+    
+    Ok(())
+}
+
+pub fn set_dataset_price(
+    ctx: Context<UpdateDataset>,
+    price: u64,
+) -> Result<()> {
+    if price == 0 {
+        return err!(DataChainError::InvalidDatasetPrice);
+    }
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    let old_price = dataset.price;
+    dataset.price = price;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetPriceSet {
+        dataset: dataset.key(),
+        old_price,
+        new_price: price,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset repriced: {} from {} to {}", dataset.name, old_price, price);
+    
+    Ok(())
+}
+
+// Existing subscribers keep their current expiry when the pricing changes
+pub fn set_dataset_pricing(
+    ctx: Context<UpdateDataset>,
+    pricing_mode: u8,
+    subscription_period: i64,
+) -> Result<()> {
+    if pricing_mode == PricingMode::OneTime as u8 {
+        if subscription_period != 0 {
+            return err!(DataChainError::InvalidParameters);
+        }
+    } else if pricing_mode == PricingMode::Subscription as u8 {
+        if subscription_period <= 0 {
+            return err!(DataChainError::InvalidParameters);
+        }
+    } else {
+        return err!(DataChainError::InvalidPricingMode);
+    }
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    dataset.pricing_mode = pricing_mode;
+    dataset.subscription_period = subscription_period;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetPricingSet {
+        dataset: dataset.key(),
+        pricing_mode,
+        subscription_period,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset pricing set: {} mode {} period {}", dataset.name, pricing_mode, subscription_period);
+    
+    Ok(())
+}
+
+pub fn set_delivery_authority(
+    ctx: Context<UpdateDataset>,
+    delivery_authority: Pubkey,
+) -> Result<()> {
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    dataset.delivery_authority = delivery_authority;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DeliveryAuthoritySet {
+        dataset: dataset.key(),
+        delivery_authority,
+    });
+    
+    msg!("Dataset delivery authority set: {} to {}", dataset.name, delivery_authority);
+    
+    Ok(())
+}
+
+pub fn delist_dataset(
+    ctx: Context<UpdateDataset>,
+) -> Result<()> {
+    set_dataset_listing(ctx, false)
+}
+
+pub fn relist_dataset(
+    ctx: Context<UpdateDataset>,
+) -> Result<()> {
+    set_dataset_listing(ctx, true)
+}
+
+// Existing purchasers keep access regardless of listing status
+fn set_dataset_listing(
+    ctx: Context<UpdateDataset>,
+    is_listed: bool,
+) -> Result<()> {
+    let dataset = &mut ctx.accounts.dataset;
+    let clock = &ctx.accounts.clock;
+    
+    if dataset.is_listed == is_listed {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    dataset.is_listed = is_listed;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(DatasetListingChanged {
+        dataset: dataset.key(),
+        is_listed,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dataset {}: {}", if is_listed { "relisted" } else { "delisted" }, dataset.name);
+    
+    Ok(())
+}
+
 // Initialize marketplace configuration
 pub fn initialize_marketplace(
     ctx: Context<InitializeMarketplace>,
@@ -395,11 +1745,23 @@ pub fn initialize_marketplace(
     marketplace_config.authority = ctx.accounts.authority.key();
     marketplace_config.platform_fee_percentage = platform_fee_percentage;
     marketplace_config.platform_fee_recipient = ctx.accounts.platform_fee_recipient.key();
+    marketplace_config.payment_mint = ctx.accounts.payment_mint.key();
+    marketplace_config.staking_fee_percentage = 0;
+    marketplace_config.staking_reward_vault = Pubkey::default();
+    marketplace_config.escrow_period = 0;
     marketplace_config.total_datasets = 0;
     marketplace_config.total_purchases = 0;
     marketplace_config.total_volume = 0;
     marketplace_config.version = MARKETPLACE_VERSION;
     
+    emit!(MarketplaceInitialized {
+        marketplace_config: marketplace_config.key(),
+        authority: marketplace_config.authority,
+        platform_fee_percentage,
+        platform_fee_recipient: marketplace_config.platform_fee_recipient,
+        payment_mint: marketplace_config.payment_mint,
+    });
+    
     msg!("Marketplace initialized with {}% fee", platform_fee_percentage);
     
     Ok(())
@@ -412,6 +1774,8 @@ pub struct InitializeMarketplace<'info> {
     
     pub platform_fee_recipient: AccountInfo<'info>,
     
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
     #[account(
         init,
         payer = authority,
@@ -421,4 +1785,47 @@ pub struct InitializeMarketplace<'info> {
     
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
-} 
\ No newline at end of file
+} 
+
+// Route a share of the platform fee to a stake pool's reward vault
+pub fn set_staking_fee_share(
+    ctx: Context<SetStakingFeeShare>,
+    staking_fee_percentage: u8,
+) -> Result<()> {
+    if staking_fee_percentage > 100 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    
+    marketplace_config.staking_fee_percentage = staking_fee_percentage;
+    marketplace_config.staking_reward_vault = ctx.accounts.stake_pool.reward_vault;
+    
+    emit!(StakingFeeShareSet {
+        marketplace_config: marketplace_config.key(),
+        stake_pool: ctx.accounts.stake_pool.key(),
+        staking_fee_percentage,
+        staking_reward_vault: marketplace_config.staking_reward_vault,
+    });
+    
+    msg!("Marketplace routes {}% of platform fees to stakers", staking_fee_percentage);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStakingFeeShare<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = marketplace_config.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    // The pool's reward vault must hold the mint the marketplace is paid in
+    #[account(
+        constraint = stake_pool.stake_mint == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
\ No newline at end of file
@@ -1,23 +1,48 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Transfer};
+use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{self, Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface, MintTo, TransferChecked, Burn};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, StateWithExtensions, transfer_fee::{TransferFee, TransferFeeConfig}},
+};
 use solana_program::{
-    program::invoke_signed,
+    program::{invoke, invoke_signed},
     sysvar::clock::Clock,
 };
+use spl_token_metadata::{
+    instruction::{create_metadata_accounts, update_metadata_accounts},
+    state::{Data, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, PREFIX as METADATA_PREFIX},
+};
 use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_MINTING, PAUSE_TRANSFERS};
+use crate::compliance::{Blocklist, BLOCKLIST_SEED};
+use crate::events::*;
 
 // Token configuration
 pub const TOKEN_DECIMALS: u8 = 9;
 pub const TOTAL_SUPPLY: u64 = 100_000_000_000_000_000; // 100 million tokens with 9 decimals
-pub const MINT_COOLDOWN: i64 = 3600; // Minting cooldown period in seconds (1 hour)
+pub const DEFAULT_EMISSION_WINDOW: i64 = 86400; // Emission window length in seconds (24 hours)
+pub const EMISSION_WINDOW_BUCKETS: i64 = 8; // Sub-windows the emission window is tracked in
+pub const EMISSION_BUCKET_SLOTS: usize = EMISSION_WINDOW_BUCKETS as usize + 1; // Current sub-window plus a full window of history
+pub const DEFAULT_EMISSION_CAP: u64 = 1_000_000_000_000_000; // 1 million tokens per emission window
 pub const AUTHORITY_TRANSFER_EXPIRY: i64 = 86400; // Authority transfer validity period in seconds (24 hours)
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+pub const BURN_VAULT_SEED: &[u8] = b"burn_vault";
+pub const BURN_AUTHORITY_SEED: &[u8] = b"burn_authority";
+pub const MAX_MEMO_LENGTH: usize = 256;
 
 // Token data structure
 #[account]
 pub struct TokenInfo {
-    // Token authority (e.g., mint authority)
+    // Token authority, gating the program-owned mint authority
     pub authority: Pubkey,
     
+    // Compliance role that can freeze accounts and manage the blocklist
+    pub freeze_authority: Pubkey,
+    
+    // M-of-N multisig that replaces the authority for minting, once created
+    pub mint_multisig: Option<Pubkey>,
+    
     // Token mint address
     pub mint: Pubkey,
     
@@ -30,9 +55,24 @@ pub struct TokenInfo {
     pub current_supply: u64,
     pub max_supply: u64,
     
-    // Last mint timestamp for cooldown
+    // Supply reserved for emission schedules and not yet released
+    pub committed_emissions: u64,
+    
+    // Burn info
+    pub total_burned: u64,
+    pub last_burn_timestamp: i64,
+    
+    // Last mint timestamp
     pub last_mint_timestamp: i64,
     
+    // Emission policy, a maximum mint amount over any rolling window
+    pub emission_window: i64,
+    pub emission_cap: u64,
+    
+    // Amounts minted per sub-window, a ring indexed by sub-window number up to the latest one minted in
+    pub emission_bucket: i64,
+    pub emission_buckets: [u64; EMISSION_BUCKET_SLOTS],
+    
     // Authority transfer info
     pub pending_authority: Option<Pubkey>,
     pub authority_transfer_expiry: i64,
@@ -40,10 +80,95 @@ pub struct TokenInfo {
     // Token parameters
     pub decimals: u8,
     
+    // Bump of the program mint authority PDA
+    pub mint_authority_bump: u8,
+    
     // Token version for future upgrades
     pub version: u8,
 }
 
+impl TokenInfo {
+    // Sub-window containing the given time, never earlier than the latest one minted in
+    pub fn emission_bucket_at(&self, now: i64) -> i64 {
+        let bucket_length = self.emission_window / EMISSION_WINDOW_BUCKETS;
+        now.div_euclid(bucket_length).max(self.emission_bucket)
+    }
+    
+    // Amount minted in the current sub-window and the full window before it, so every
+    // span of emission_window seconds ending now is covered
+    pub fn minted_in_window(&self, now: i64) -> u64 {
+        let current = self.emission_bucket_at(now);
+        
+        (current - EMISSION_WINDOW_BUCKETS..=self.emission_bucket)
+            .map(|bucket| self.emission_buckets[bucket.rem_euclid(EMISSION_BUCKET_SLOTS as i64) as usize])
+            .fold(0u64, |total, minted| total.saturating_add(minted))
+    }
+    
+    // Amount that can still be minted in the rolling window ending at the given time
+    pub fn remaining_allowance(&self, now: i64) -> u64 {
+        self.emission_cap.saturating_sub(self.minted_in_window(now))
+    }
+    
+    // Check the emission cap and max supply for a mint of the given amount
+    pub fn check_mint_allowed(&self, amount: u64, now: i64) -> Result<()> {
+        // Check the remaining allowance of the current emission window; the error carries the
+        // requested and remaining amounts as compared values
+        let remaining = self.remaining_allowance(now);
+        if amount > remaining {
+            let error = if remaining == 0 {
+                error!(DataChainError::MintingCooldown)
+            } else {
+                error!(DataChainError::EmissionCapExceeded)
+            };
+            
+            return Err(error.with_values((amount, remaining)));
+        }
+        
+        // Check if max supply would be exceeded, leaving room for committed emissions
+        if amount > self.uncommitted_supply() {
+            return err!(DataChainError::MaxSupplyReached);
+        }
+        
+        Ok(())
+    }
+    
+    // Supply that can still be minted or committed to new emission schedules
+    pub fn uncommitted_supply(&self) -> u64 {
+        self.max_supply
+            .saturating_sub(self.current_supply)
+            .saturating_sub(self.committed_emissions)
+    }
+    
+    // Record a completed mint against the supply and emission window
+    pub fn record_mint(&mut self, amount: u64, now: i64) -> Result<()> {
+        // Clear the slots of sub-windows that passed since the last mint
+        let current = self.emission_bucket_at(now);
+        let elapsed = (current - self.emission_bucket).min(EMISSION_BUCKET_SLOTS as i64);
+        for bucket in current - elapsed + 1..=current {
+            self.emission_buckets[bucket.rem_euclid(EMISSION_BUCKET_SLOTS as i64) as usize] = 0;
+        }
+        self.emission_bucket = current;
+        
+        let slot = &mut self.emission_buckets[current.rem_euclid(EMISSION_BUCKET_SLOTS as i64) as usize];
+        *slot = slot.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+        self.current_supply = self.current_supply.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+        self.last_mint_timestamp = now;
+        
+        Ok(())
+    }
+    
+    // Change the emission policy, carrying the amount minted in the current window into the new sub-windows
+    pub fn set_emission_policy(&mut self, emission_window: i64, emission_cap: u64, now: i64) {
+        let minted = self.minted_in_window(now);
+        
+        self.emission_window = emission_window;
+        self.emission_cap = emission_cap;
+        self.emission_bucket = now.div_euclid(emission_window / EMISSION_WINDOW_BUCKETS);
+        self.emission_buckets = [0; EMISSION_BUCKET_SLOTS];
+        self.emission_buckets[self.emission_bucket.rem_euclid(EMISSION_BUCKET_SLOTS as i64) as usize] = minted;
+    }
+}
+
 // Token authority transfer request
 #[account]
 pub struct AuthorityTransferRequest {
@@ -57,6 +182,49 @@ pub struct AuthorityTransferRequest {
     pub expiry_timestamp: i64,
 }
 
+// Context for transferring tokens with an optional memo
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    pub sender: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+    
+    #[account(
+        mut,
+        constraint = sender_token.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = sender_token.owner == sender.key() @ DataChainError::Unauthorized
+    )]
+    pub sender_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = recipient_token.mint == mint.key() @ DataChainError::InvalidParameters
+    )]
+    pub recipient_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_TRANSFERS) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&sender.key()) @ DataChainError::WalletBlocked,
+        constraint = !blocklist.is_blocked(&recipient_token.owner) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// CHECK: SPL Memo program
+    #[account(address = spl_memo::id())]
+    pub memo_program: AccountInfo<'info>,
+}
+
 // Context for authority transfer request
 #[derive(Accounts)]
 pub struct RequestAuthorityTransfer<'info> {
@@ -91,6 +259,173 @@ pub struct AcceptAuthorityTransfer<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
+// Context for cancelling a pending authority transfer
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    pub current_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.authority == current_authority.key() @ DataChainError::Unauthorized,
+        constraint = token_info.pending_authority.is_some() @ DataChainError::InvalidParameters
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+// Context for burning tokens from the holder's own account
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+    
+    #[account(
+        mut,
+        constraint = owner_token.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub owner_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Context for creating the treasury burn vault
+#[derive(Accounts)]
+pub struct InitializeBurnVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+    
+    /// CHECK: PDA signing for the burn vault
+    #[account(
+        seeds = [BURN_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub burn_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [BURN_VAULT_SEED, token_info.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = burn_authority,
+        token::token_program = token_program,
+    )]
+    pub burn_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Context for the treasury burning tokens accumulated in the burn vault
+#[derive(Accounts)]
+pub struct ExecuteBurn<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+    
+    /// CHECK: PDA signing for the burn vault
+    #[account(
+        seeds = [BURN_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub burn_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [BURN_VAULT_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub burn_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for updating the token metadata
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    /// CHECK: Program mint authority PDA, also the metadata update authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    /// CHECK: Metadata account owned by the token metadata program
+    #[account(
+        mut,
+        seeds = [METADATA_PREFIX.as_bytes(), token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: AccountInfo<'info>,
+    
+    /// CHECK: Token metadata program
+    #[account(address = spl_token_metadata::id())]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+// Context for reading the remaining emission allowance
+#[derive(Accounts)]
+pub struct GetEmissionAllowance<'info> {
+    pub token_info: Account<'info, TokenInfo>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Validate token metadata against the metadata program limits
+fn validate_token_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+        return err!(DataChainError::InvalidTokenMetadata);
+    }
+    
+    if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LENGTH {
+        return err!(DataChainError::InvalidTokenMetadata);
+    }
+    
+    if uri.len() > MAX_URI_LENGTH {
+        return err!(DataChainError::InvalidTokenMetadata);
+    }
+    
+    Ok(())
+}
+
 // Implementation of token functions
 pub fn initialize_token(
     ctx: Context<InitializeToken>,
@@ -99,25 +434,85 @@ pub fn initialize_token(
     uri: String,
     decimals: u8,
 ) -> Result<()> {
+    validate_token_metadata(&name, &symbol, &uri)?;
+    
     let token_info = &mut ctx.accounts.token_info;
     let authority = &ctx.accounts.authority;
     let mint = &ctx.accounts.mint;
     let clock = &ctx.accounts.clock;
+    let mint_authority_bump = *ctx.bumps.get("mint_authority").ok_or(DataChainError::SystemError)?;
+    
+    // Create the standard metadata account so wallets and explorers can name the mint
+    let token_info_key = token_info.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[mint_authority_bump],
+    ];
+    
+    let metadata_ix = create_metadata_accounts(
+        ctx.accounts.token_metadata_program.key(),
+        ctx.accounts.metadata.key(),
+        mint.key(),
+        ctx.accounts.mint_authority.key(),
+        authority.key(),
+        ctx.accounts.mint_authority.key(),
+        name.clone(),
+        symbol.clone(),
+        uri.clone(),
+        None,
+        0,
+        true,
+        true,
+    );
+    
+    invoke_signed(
+        &metadata_ix,
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            mint.to_account_info(),
+            ctx.accounts.mint_authority.to_account_info(),
+            authority.to_account_info(),
+            ctx.accounts.mint_authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+        ],
+        &[&seeds[..]],
+    )?;
     
     // Initialize token info
     token_info.authority = authority.key();
+    token_info.freeze_authority = authority.key();
+    token_info.mint_multisig = None;
     token_info.mint = mint.key();
     token_info.name = name;
     token_info.symbol = symbol;
     token_info.uri = uri;
     token_info.current_supply = 0;
     token_info.max_supply = TOTAL_SUPPLY;
+    token_info.committed_emissions = 0;
+    token_info.total_burned = 0;
+    token_info.last_burn_timestamp = 0;
     token_info.last_mint_timestamp = clock.unix_timestamp;
+    token_info.emission_window = DEFAULT_EMISSION_WINDOW;
+    token_info.emission_cap = DEFAULT_EMISSION_CAP;
+    token_info.emission_bucket = clock.unix_timestamp.div_euclid(DEFAULT_EMISSION_WINDOW / EMISSION_WINDOW_BUCKETS);
+    token_info.emission_buckets = [0; EMISSION_BUCKET_SLOTS];
     token_info.pending_authority = None;
     token_info.authority_transfer_expiry = 0;
     token_info.decimals = decimals;
+    token_info.mint_authority_bump = mint_authority_bump;
     token_info.version = 1;
     
+    emit!(TokenInitialized {
+        token_info: token_info.key(),
+        mint: mint.key(),
+        authority: authority.key(),
+        decimals,
+        max_supply: token_info.max_supply,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("DATA token initialized successfully");
     
     Ok(())
@@ -128,77 +523,127 @@ pub fn mint_tokens(
     amount: u64,
 ) -> Result<()> {
     let token_info = &mut ctx.accounts.token_info;
-    let mint = &ctx.accounts.mint;
-    let authority = &ctx.accounts.authority;
     let recipient = &ctx.accounts.recipient;
-    let token_program = &ctx.accounts.token_program;
     let clock = &ctx.accounts.clock;
     
-    // Check if minting cooldown period has passed
-    if clock.unix_timestamp - token_info.last_mint_timestamp < MINT_COOLDOWN {
-        return err!(DataChainError::MintingCooldown);
-    }
+    // Check emission cap and max supply
+    token_info.check_mint_allowed(amount, clock.unix_timestamp)?;
     
-    // Check if max supply would be exceeded
-    if token_info.current_supply.checked_add(amount).ok_or(DataChainError::InvalidParameters)? > token_info.max_supply {
-        return err!(DataChainError::MaxSupplyReached);
-    }
-    
-    // Create mint instruction
-    let cpi_accounts = MintTo {
-        mint: mint.to_account_info(),
-        to: recipient.to_account_info(),
-        authority: authority.to_account_info(),
-    };
-    
-    // Execute mint instruction
-    token::mint_to(
-        CpiContext::new(
-            token_program.to_account_info(),
-            cpi_accounts,
-        ),
+    // Mint signed by the program mint authority
+    mint_with_program_authority(
+        token_info,
+        ctx.accounts.mint.to_account_info(),
+        recipient.to_account_info(),
+        &ctx.accounts.mint_authority,
+        ctx.accounts.token_program.to_account_info(),
         amount,
     )?;
     
     // Update token info
-    token_info.current_supply = token_info.current_supply.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
-    token_info.last_mint_timestamp = clock.unix_timestamp;
+    token_info.record_mint(amount, clock.unix_timestamp)?;
+    
+    emit!(TokenMinted {
+        token_info: token_info.key(),
+        mint: token_info.mint,
+        recipient: recipient.key(),
+        amount,
+        current_supply: token_info.current_supply,
+        timestamp: clock.unix_timestamp,
+    });
     
     msg!("Minted {} DATA tokens to {}", amount, recipient.key());
     
     Ok(())
 }
 
+// Remaining allowance of the current emission window, returned to the caller
+pub fn get_emission_allowance(
+    ctx: Context<GetEmissionAllowance>,
+) -> Result<u64> {
+    Ok(ctx.accounts.token_info.remaining_allowance(ctx.accounts.clock.unix_timestamp))
+}
+
+// Emission windows must divide evenly into sub-windows, and the cap must allow some minting
+pub fn validate_emission_policy(emission_window: i64, emission_cap: u64) -> Result<()> {
+    if emission_window <= 0 || emission_window % EMISSION_WINDOW_BUCKETS != 0 || emission_cap == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    Ok(())
+}
+
+// Apply the emission policy of an executed governance proposal
+pub fn apply_emission_policy(
+    token_info: &mut Account<TokenInfo>,
+    emission_window: i64,
+    emission_cap: u64,
+    now: i64,
+) -> Result<()> {
+    validate_emission_policy(emission_window, emission_cap)?;
+    
+    token_info.set_emission_policy(emission_window, emission_cap, now);
+    
+    emit!(EmissionPolicyUpdated {
+        token_info: token_info.key(),
+        emission_window,
+        emission_cap,
+    });
+    
+    msg!("Emission policy updated: {} DATA tokens per {} seconds", emission_cap, emission_window);
+    
+    Ok(())
+}
+
 pub fn transfer_tokens(
     ctx: Context<TransferTokens>,
     amount: u64,
     memo: Option<String>,
 ) -> Result<()> {
+    // Validate memo
+    if let Some(memo_text) = &memo {
+        if memo_text.is_empty() || memo_text.len() > MAX_MEMO_LENGTH {
+            return err!(DataChainError::InvalidMemo);
+        }
+    }
+    
     let sender_token = &ctx.accounts.sender_token;
     let recipient_token = &ctx.accounts.recipient_token;
     let token_program = &ctx.accounts.token_program;
     
     // Create transfer instruction
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: sender_token.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
         to: recipient_token.to_account_info(),
         authority: ctx.accounts.sender.to_account_info(),
     };
     
     // Execute transfer instruction
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
             cpi_accounts,
         ),
         amount,
+        ctx.accounts.mint.decimals,
     )?;
     
-    // Log memo if provided
-    if let Some(memo_text) = memo {
-        msg!("Memo: {}", memo_text);
+    // Record memo through the SPL Memo program
+    if let Some(memo_text) = &memo {
+        invoke(
+            &spl_memo::build_memo(memo_text.as_bytes(), &[]),
+            &[ctx.accounts.memo_program.to_account_info()],
+        )?;
     }
     
+    emit!(TokensTransferred {
+        mint: ctx.accounts.mint.key(),
+        sender: sender_token.owner,
+        recipient: recipient_token.owner,
+        amount,
+        memo,
+    });
+    
     msg!("Transferred {} DATA tokens from {} to {}", 
          amount, 
          sender_token.owner, 
@@ -207,6 +652,178 @@ pub fn transfer_tokens(
     Ok(())
 }
 
+pub fn update_token_metadata(
+    ctx: Context<UpdateTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    validate_token_metadata(&name, &symbol, &uri)?;
+    
+    let token_info = &mut ctx.accounts.token_info;
+    
+    // Update the metadata account, signed by the program update authority
+    let token_info_key = token_info.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[token_info.mint_authority_bump],
+    ];
+    
+    let metadata_ix = update_metadata_accounts(
+        ctx.accounts.token_metadata_program.key(),
+        ctx.accounts.metadata.key(),
+        ctx.accounts.mint_authority.key(),
+        None,
+        Some(Data {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+        }),
+        None,
+    );
+    
+    invoke_signed(
+        &metadata_ix,
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.mint_authority.to_account_info(),
+        ],
+        &[&seeds[..]],
+    )?;
+    
+    // Keep token info in sync
+    token_info.name = name;
+    token_info.symbol = symbol;
+    token_info.uri = uri;
+    
+    emit!(TokenMetadataUpdated {
+        token_info: token_info.key(),
+        name: token_info.name.clone(),
+        symbol: token_info.symbol.clone(),
+        uri: token_info.uri.clone(),
+    });
+    
+    msg!("Token metadata updated: {} ({})", token_info.name, token_info.symbol);
+    
+    Ok(())
+}
+
+pub fn burn_tokens(
+    ctx: Context<BurnTokens>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let token_info = &mut ctx.accounts.token_info;
+    
+    // Create burn instruction
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.owner_token.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    
+    // Execute burn instruction
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        amount,
+    )?;
+    
+    // Update token info
+    token_info.current_supply = token_info.current_supply.checked_sub(amount).ok_or(DataChainError::InvalidParameters)?;
+    token_info.total_burned = token_info.total_burned.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(TokensBurned {
+        token_info: token_info.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+        current_supply: token_info.current_supply,
+        total_burned: token_info.total_burned,
+    });
+    
+    msg!("Burned {} DATA tokens from {}", amount, ctx.accounts.owner.key());
+    
+    Ok(())
+}
+
+pub fn initialize_burn_vault(
+    ctx: Context<InitializeBurnVault>,
+) -> Result<()> {
+    emit!(BurnVaultInitialized {
+        token_info: ctx.accounts.token_info.key(),
+        burn_vault: ctx.accounts.burn_vault.key(),
+    });
+    
+    msg!("Burn vault initialized: {}", ctx.accounts.burn_vault.key());
+    
+    Ok(())
+}
+
+pub fn execute_burn(
+    ctx: Context<ExecuteBurn>,
+    amount: u64,
+) -> Result<()> {
+    let token_info = &mut ctx.accounts.token_info;
+    let burn_vault = &ctx.accounts.burn_vault;
+    let clock = &ctx.accounts.clock;
+    
+    if amount == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if amount > burn_vault.amount {
+        return err!(DataChainError::InsufficientBalance);
+    }
+    
+    let token_info_key = token_info.key();
+    let seeds = &[
+        BURN_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[*ctx.bumps.get("burn_authority").ok_or(DataChainError::SystemError)?],
+    ];
+    
+    // Burn from the fee vault, signed by the vault authority PDA
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: burn_vault.to_account_info(),
+        authority: ctx.accounts.burn_authority.to_account_info(),
+    };
+    
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+        amount,
+    )?;
+    
+    // Update token info
+    token_info.current_supply = token_info.current_supply.checked_sub(amount).ok_or(DataChainError::InvalidParameters)?;
+    token_info.total_burned = token_info.total_burned.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+    token_info.last_burn_timestamp = clock.unix_timestamp;
+    
+    emit!(TreasuryBurnExecuted {
+        token_info: token_info.key(),
+        amount,
+        current_supply: token_info.current_supply,
+        total_burned: token_info.total_burned,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Treasury burned {} DATA tokens, {} burned in total", amount, token_info.total_burned);
+    
+    Ok(())
+}
+
 pub fn request_authority_transfer(
     ctx: Context<RequestAuthorityTransfer>,
 ) -> Result<()> {
@@ -217,6 +834,13 @@ pub fn request_authority_transfer(
     token_info.pending_authority = Some(ctx.accounts.new_authority.key());
     token_info.authority_transfer_expiry = clock.unix_timestamp + AUTHORITY_TRANSFER_EXPIRY;
     
+    emit!(AuthorityTransferRequested {
+        token_info: token_info.key(),
+        current_authority: ctx.accounts.current_authority.key(),
+        new_authority: ctx.accounts.new_authority.key(),
+        expiry_timestamp: token_info.authority_transfer_expiry,
+    });
+    
     msg!("Authority transfer requested to {}", ctx.accounts.new_authority.key());
     
     Ok(())
@@ -226,43 +850,164 @@ pub fn accept_authority_transfer(
     ctx: Context<AcceptAuthorityTransfer>,
 ) -> Result<()> {
     let token_info = &mut ctx.accounts.token_info;
+    let new_authority = ctx.accounts.new_authority.key();
     
     // Update authority
-    token_info.authority = ctx.accounts.new_authority.key();
+    token_info.authority = new_authority;
+    token_info.pending_authority = None;
+    token_info.authority_transfer_expiry = 0;
+    
+    emit!(AuthorityTransferAccepted {
+        token_info: token_info.key(),
+        new_authority,
+    });
+    
+    msg!("Authority transfer accepted by {}", new_authority);
+    
+    Ok(())
+}
+
+pub fn cancel_authority_transfer(
+    ctx: Context<CancelAuthorityTransfer>,
+) -> Result<()> {
+    let token_info = &mut ctx.accounts.token_info;
+    let current_authority = ctx.accounts.current_authority.key();
+    
+    // Clear pending transfer
     token_info.pending_authority = None;
     token_info.authority_transfer_expiry = 0;
     
-    msg!("Authority transfer accepted by {}", ctx.accounts.new_authority.key());
+    emit!(AuthorityTransferCancelled {
+        token_info: token_info.key(),
+        authority: current_authority,
+    });
+    
+    msg!("Authority transfer cancelled by {}", current_authority);
     
     Ok(())
 }
 
+// Mint tokens signed by the program mint authority PDA
+pub fn mint_with_program_authority<'info>(
+    token_info: &Account<'info, TokenInfo>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let token_info_key = token_info.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        token_info_key.as_ref(),
+        &[token_info.mint_authority_bump],
+    ];
+    
+    let cpi_accounts = MintTo {
+        mint,
+        to,
+        authority: mint_authority.to_account_info(),
+    };
+    
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program,
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+        amount,
+    )
+}
+
+// Amount to send so the recipient receives `net_amount` after any Token-2022 transfer fee
+pub fn gross_amount_for_net(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    // Legacy mints have no extensions
+    if *mint.owner != spl_token_2022::ID || net_amount == 0 {
+        return Ok(net_amount);
+    }
+    
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee_config = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config,
+        Err(_) => return Ok(net_amount),
+    };
+    
+    let transfer_fee = fee_config.get_epoch_fee(Clock::get()?.epoch);
+    gross_amount_for_fee(transfer_fee, net_amount)
+}
+
+// Gross amount under a given transfer fee, the smallest that leaves `net_amount` after the fee
+pub fn gross_amount_for_fee(transfer_fee: &TransferFee, net_amount: u64) -> Result<u64> {
+    let basis_points = u16::from(transfer_fee.transfer_fee_basis_points) as u128;
+    let maximum_fee = u64::from(transfer_fee.maximum_fee);
+    
+    if basis_points == 0 || maximum_fee == 0 || net_amount == 0 {
+        return Ok(net_amount);
+    }
+    
+    // Fee is ceil(gross * bps / 10000), capped at the maximum fee; an uncapped fee may set it to u64::MAX
+    let capped_gross = net_amount.saturating_add(maximum_fee);
+    let mut gross_amount = if basis_points >= 10_000 {
+        net_amount.checked_add(maximum_fee).ok_or(DataChainError::InvalidParameters)?
+    } else {
+        let denominator = 10_000u128 - basis_points;
+        let gross = (net_amount as u128)
+            .checked_mul(10_000)
+            .ok_or(DataChainError::InvalidParameters)?
+            .checked_add(denominator - 1)
+            .ok_or(DataChainError::InvalidParameters)?
+            / denominator;
+        u64::try_from(gross).map_err(|_| DataChainError::InvalidParameters)?.min(capped_gross)
+    };
+    
+    // Correct for fee rounding
+    let fee = transfer_fee.calculate_fee(gross_amount).ok_or(DataChainError::InvalidParameters)?;
+    if gross_amount - fee < net_amount {
+        gross_amount = gross_amount.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    }
+    
+    Ok(gross_amount)
+}
+
 // Account validation structs with their implementation would be added
 #[derive(Accounts)]
 pub struct MintTokens<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    #[account(
-        mut,
-        constraint = mint.mint_authority.unwrap() == authority.key() @ DataChainError::Unauthorized
-    )]
-    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
     
     #[account(
         mut,
         constraint = recipient.mint == mint.key() @ DataChainError::InvalidParameters
     )]
-    pub recipient: Account<'info, TokenAccount>,
+    pub recipient: InterfaceAccount<'info, InterfaceTokenAccount>,
     
     #[account(
         mut,
         constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
-        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized,
+        constraint = token_info.mint_multisig.is_none() @ DataChainError::Unauthorized
     )]
     pub token_info: Account<'info, TokenInfo>,
     
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Program mint authority PDA
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_MINTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
     pub clock: Sysvar<'info, Clock>,
 }
 
@@ -274,20 +1019,211 @@ pub struct InitializeToken<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + std::mem::size_of::<TokenInfo>(),
+        space = 8 + std::mem::size_of::<TokenInfo>() + MAX_NAME_LENGTH + MAX_SYMBOL_LENGTH + MAX_URI_LENGTH,
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    /// CHECK: Program mint authority PDA
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    // Created under the legacy token program, which the metadata program requires
     #[account(
         init,
         payer = authority,
         mint::decimals = TOKEN_DECIMALS,
-        mint::authority = authority.key(),
+        mint::authority = mint_authority.key(),
+        mint::freeze_authority = mint_authority.key(),
     )]
     pub mint: Account<'info, Mint>,
     
+    /// CHECK: Metadata account created by the token metadata program
+    #[account(
+        mut,
+        seeds = [METADATA_PREFIX.as_bytes(), token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: AccountInfo<'info>,
+    
+    /// CHECK: Token metadata program
+    #[account(address = spl_token_metadata::id())]
+    pub token_metadata_program: AccountInfo<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub clock: Sysvar<'info, Clock>,
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const BUCKET_LENGTH: i64 = DEFAULT_EMISSION_WINDOW / EMISSION_WINDOW_BUCKETS;
+    
+    fn token_info(emission_cap: u64, now: i64) -> TokenInfo {
+        TokenInfo {
+            authority: Pubkey::default(),
+            freeze_authority: Pubkey::default(),
+            mint_multisig: None,
+            mint: Pubkey::default(),
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            current_supply: 0,
+            max_supply: TOTAL_SUPPLY,
+            committed_emissions: 0,
+            total_burned: 0,
+            last_burn_timestamp: 0,
+            last_mint_timestamp: now,
+            emission_window: DEFAULT_EMISSION_WINDOW,
+            emission_cap,
+            emission_bucket: now.div_euclid(BUCKET_LENGTH),
+            emission_buckets: [0; EMISSION_BUCKET_SLOTS],
+            pending_authority: None,
+            authority_transfer_expiry: 0,
+            decimals: TOKEN_DECIMALS,
+            mint_authority_bump: 255,
+            version: 1,
+        }
+    }
+    
+    #[test]
+    fn emission_cap_holds_across_window_boundary() {
+        let start = 1_700_000_000;
+        let mut info = token_info(1_000, start);
+        
+        // Mint the full cap just before a fixed window would have reset
+        let now = start + DEFAULT_EMISSION_WINDOW - 1;
+        info.check_mint_allowed(1_000, now).unwrap();
+        info.record_mint(1_000, now).unwrap();
+        
+        assert!(info.check_mint_allowed(1, now + 1).is_err());
+        assert!(info.check_mint_allowed(1, now + DEFAULT_EMISSION_WINDOW).is_err());
+        
+        // The allowance returns once the mint's sub-window leaves the rolling window
+        assert_eq!(info.remaining_allowance(now + DEFAULT_EMISSION_WINDOW + BUCKET_LENGTH), 1_000);
+    }
+    
+    #[test]
+    fn emission_allowance_frees_up_per_sub_window() {
+        let start = 1_700_000_000;
+        let mut info = token_info(1_000, start);
+        
+        info.record_mint(600, start).unwrap();
+        info.record_mint(400, start + DEFAULT_EMISSION_WINDOW / 2).unwrap();
+        assert_eq!(info.remaining_allowance(start + DEFAULT_EMISSION_WINDOW / 2), 0);
+        
+        // Only the first mint has aged out
+        let later = start + DEFAULT_EMISSION_WINDOW + BUCKET_LENGTH;
+        assert_eq!(info.remaining_allowance(later), 600);
+        
+        // Recording after a long gap clears the stale sub-windows
+        info.record_mint(100, later + 10 * DEFAULT_EMISSION_WINDOW).unwrap();
+        assert_eq!(info.minted_in_window(later + 10 * DEFAULT_EMISSION_WINDOW), 100);
+        assert_eq!(info.current_supply, 1_100);
+    }
+    
+    #[test]
+    fn emission_policy_change_keeps_minted_amount() {
+        let start = 1_700_000_000;
+        let mut info = token_info(1_000, start);
+        
+        info.record_mint(800, start).unwrap();
+        info.set_emission_policy(DEFAULT_EMISSION_WINDOW / 2, 1_000, start + 60);
+        
+        assert_eq!(info.remaining_allowance(start + 60), 200);
+        assert!(info.check_mint_allowed(201, start + 60).is_err());
+    }
+    
+    #[test]
+    fn emission_cap_error_reports_the_remaining_allowance() {
+        let start = 1_700_000_000;
+        let mut info = token_info(1_000, start);
+        
+        info.record_mint(700, start).unwrap();
+        
+        match info.check_mint_allowed(500, start) {
+            Err(Error::AnchorError(error)) => {
+                assert_eq!(error.error_name, "EmissionCapExceeded");
+                match error.compared_values {
+                    Some(ComparedValues::Values((requested, remaining))) => {
+                        assert_eq!(requested, "500");
+                        assert_eq!(remaining, "300");
+                    }
+                    other => panic!("unexpected compared values: {:?}", other),
+                }
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn mint_cannot_exceed_max_supply() {
+        let start = 1_700_000_000;
+        let mut info = token_info(u64::MAX, start);
+        info.current_supply = TOTAL_SUPPLY - 10;
+        
+        assert!(info.check_mint_allowed(10, start).is_ok());
+        assert!(info.check_mint_allowed(11, start).is_err());
+        
+        // Committed emissions are held back from other mints
+        info.committed_emissions = 4;
+        assert_eq!(info.uncommitted_supply(), 6);
+        assert!(info.check_mint_allowed(7, start).is_err());
+    }
+    
+    fn transfer_fee(basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+    
+    #[test]
+    fn gross_amount_rounds_the_fee_up() {
+        // The fee on the gross amount is rounded up, so the smallest fee is a whole token unit
+        assert_eq!(gross_amount_for_fee(&transfer_fee(1, u64::MAX), 1).unwrap(), 2);
+        assert_eq!(gross_amount_for_fee(&transfer_fee(1, u64::MAX), 10_000).unwrap(), 10_002);
+        assert_eq!(gross_amount_for_fee(&transfer_fee(33, u64::MAX), 1_000).unwrap(), 1_004);
+        
+        // Near-total fees multiply the amount
+        assert_eq!(gross_amount_for_fee(&transfer_fee(9_999, u64::MAX), 1).unwrap(), 10_000);
+        assert_eq!(gross_amount_for_fee(&transfer_fee(9_999, u64::MAX), 3).unwrap(), 30_000);
+    }
+    
+    #[test]
+    fn gross_amount_stops_at_the_maximum_fee() {
+        let fee = transfer_fee(500, 10);
+        
+        // Below, at and above the amount where the percentage fee reaches the maximum
+        assert_eq!(gross_amount_for_fee(&fee, 170).unwrap(), 179);
+        assert_eq!(gross_amount_for_fee(&fee, 189).unwrap(), 199);
+        assert_eq!(gross_amount_for_fee(&fee, 190).unwrap(), 200);
+        assert_eq!(gross_amount_for_fee(&fee, 191).unwrap(), 201);
+        assert_eq!(gross_amount_for_fee(&fee, 1_000_000).unwrap(), 1_000_010);
+        
+        // A 100% fee is always the maximum fee
+        let fee = transfer_fee(10_000, 25);
+        assert_eq!(gross_amount_for_fee(&fee, 1).unwrap(), 26);
+        assert_eq!(gross_amount_for_fee(&fee, 1_000).unwrap(), 1_025);
+    }
+    
+    #[test]
+    fn gross_amount_without_a_fee_is_the_net_amount() {
+        assert_eq!(gross_amount_for_fee(&transfer_fee(0, 1_000), 500).unwrap(), 500);
+        assert_eq!(gross_amount_for_fee(&transfer_fee(100, 0), 500).unwrap(), 500);
+        assert_eq!(gross_amount_for_fee(&transfer_fee(100, 1_000), 0).unwrap(), 0);
+    }
+    
+    #[test]
+    fn gross_amount_overflow_is_an_error() {
+        assert!(gross_amount_for_fee(&transfer_fee(10_000, 1), u64::MAX).is_err());
+        assert!(gross_amount_for_fee(&transfer_fee(9_000, u64::MAX), u64::MAX / 2).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_MINTING};
+use crate::data_token::{self, TokenInfo, MINT_AUTHORITY_SEED};
+use crate::events::*;
+
+// Constants for emission schedules
+pub const EMISSION_SCHEDULE_VERSION: u8 = 1;
+pub const MAX_EMISSION_POINTS: usize = 16;
+
+// Point on a release curve: the cumulative amount unlocked at a timestamp
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EmissionPoint {
+    pub timestamp: i64,
+    pub cumulative_amount: u64,
+}
+
+// Emission schedule structure
+#[account]
+pub struct EmissionSchedule {
+    // Authority that created the schedule
+    pub authority: Pubkey,
+    
+    // Token info and the vault receiving released tokens
+    pub token_info: Pubkey,
+    pub rewards_vault: Pubkey,
+    
+    // Piecewise-linear release curve, linear between consecutive points
+    pub points: Vec<EmissionPoint>,
+    
+    // Release info
+    pub released_amount: u64,
+    pub last_crank_timestamp: i64,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl EmissionSchedule {
+    // Total amount released once the curve completes
+    pub fn total_amount(&self) -> u64 {
+        self.points.last().map(|point| point.cumulative_amount).unwrap_or(0)
+    }
+    
+    // Cumulative amount unlocked at the given timestamp
+    pub fn unlocked_amount(&self, now: i64) -> Result<u64> {
+        let first = match self.points.first() {
+            Some(point) => point,
+            None => return Ok(0),
+        };
+        
+        if now < first.timestamp {
+            return Ok(0);
+        }
+        
+        for segment in self.points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            if now >= end.timestamp {
+                continue;
+            }
+            
+            // Interpolate within the segment
+            let elapsed = now.checked_sub(start.timestamp).ok_or(DataChainError::InvalidTimestamp)?;
+            let duration = end.timestamp.checked_sub(start.timestamp).ok_or(DataChainError::InvalidTimestamp)?;
+            let segment_amount = end.cumulative_amount.checked_sub(start.cumulative_amount).ok_or(DataChainError::InvalidParameters)?;
+            
+            let unlocked = (segment_amount as u128)
+                .checked_mul(elapsed as u128)
+                .ok_or(DataChainError::InvalidParameters)?
+                .checked_div(duration as u128)
+                .ok_or(DataChainError::InvalidParameters)? as u64;
+            
+            return Ok(start.cumulative_amount.checked_add(unlocked).ok_or(DataChainError::InvalidParameters)?);
+        }
+        
+        Ok(self.total_amount())
+    }
+}
+
+// Validate a release curve: increasing timestamps and non-decreasing amounts
+fn validate_emission_points(points: &[EmissionPoint]) -> Result<()> {
+    if points.is_empty() || points.len() > MAX_EMISSION_POINTS {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    for segment in points.windows(2) {
+        if segment[1].timestamp <= segment[0].timestamp {
+            return err!(DataChainError::InvalidTimestamp);
+        }
+        
+        if segment[1].cumulative_amount < segment[0].cumulative_amount {
+            return err!(DataChainError::InvalidParameters);
+        }
+    }
+    
+    if points[points.len() - 1].cumulative_amount == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    Ok(())
+}
+
+// Context for creating an emission schedule
+#[derive(Accounts)]
+pub struct CreateEmissionSchedule<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized,
+        constraint = token_info.mint_multisig.is_none() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<EmissionSchedule>() + (MAX_EMISSION_POINTS * std::mem::size_of::<EmissionPoint>()),
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(
+        constraint = rewards_vault.mint == token_info.mint @ DataChainError::InvalidParameters
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for releasing unlocked emissions, callable by anyone
+#[derive(Accounts)]
+pub struct CrankEmission<'info> {
+    pub cranker: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = emission_schedule.token_info == token_info.key() @ DataChainError::InvalidParameters
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    /// CHECK: Program mint authority PDA
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = rewards_vault.key() == emission_schedule.rewards_vault @ DataChainError::InvalidParameters
+    )]
+    pub rewards_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_MINTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of emission functions
+pub fn create_emission_schedule(
+    ctx: Context<CreateEmissionSchedule>,
+    points: Vec<EmissionPoint>,
+) -> Result<()> {
+    validate_emission_points(&points)?;
+    
+    let token_info = &mut ctx.accounts.token_info;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    
+    // Reserve the schedule's total so schedules can never promise more than the remaining supply
+    let total_amount = points[points.len() - 1].cumulative_amount;
+    if total_amount > token_info.uncommitted_supply() {
+        return err!(DataChainError::MaxSupplyReached);
+    }
+    
+    token_info.committed_emissions = token_info.committed_emissions.checked_add(total_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    // Initialize emission schedule
+    emission_schedule.authority = ctx.accounts.authority.key();
+    emission_schedule.token_info = token_info.key();
+    emission_schedule.rewards_vault = ctx.accounts.rewards_vault.key();
+    emission_schedule.points = points;
+    emission_schedule.released_amount = 0;
+    emission_schedule.last_crank_timestamp = ctx.accounts.clock.unix_timestamp;
+    emission_schedule.version = EMISSION_SCHEDULE_VERSION;
+    
+    emit!(EmissionScheduleCreated {
+        emission_schedule: emission_schedule.key(),
+        rewards_vault: emission_schedule.rewards_vault,
+        total_amount: emission_schedule.total_amount(),
+    });
+    
+    msg!("Emission schedule created: {} DATA to {}", emission_schedule.total_amount(), emission_schedule.rewards_vault);
+    
+    Ok(())
+}
+
+pub fn crank_emission(
+    ctx: Context<CrankEmission>,
+) -> Result<()> {
+    let token_info = &mut ctx.accounts.token_info;
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    let clock = &ctx.accounts.clock;
+    
+    let unlocked = emission_schedule.unlocked_amount(clock.unix_timestamp)?;
+    let unreleased = unlocked.checked_sub(emission_schedule.released_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    if unreleased == 0 {
+        return err!(DataChainError::NothingToClaim);
+    }
+    
+    // Release what the emission cap allows now; the rest waits for a later crank
+    let allowance = token_info.remaining_allowance(clock.unix_timestamp);
+    if allowance == 0 {
+        return err!(DataChainError::MintingCooldown);
+    }
+    
+    let releasable = unreleased.min(allowance);
+    
+    // The released amount leaves the schedule's reservation and counts as an ordinary mint
+    token_info.committed_emissions = token_info.committed_emissions.checked_sub(releasable).ok_or(DataChainError::InvalidParameters)?;
+    token_info.check_mint_allowed(releasable, clock.unix_timestamp)?;
+    
+    // Mint the released amount into the rewards vault
+    data_token::mint_with_program_authority(
+        token_info,
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.rewards_vault.to_account_info(),
+        &ctx.accounts.mint_authority,
+        ctx.accounts.token_program.to_account_info(),
+        releasable,
+    )?;
+    
+    token_info.record_mint(releasable, clock.unix_timestamp)?;
+    emission_schedule.released_amount = emission_schedule.released_amount.checked_add(releasable).ok_or(DataChainError::InvalidParameters)?;
+    emission_schedule.last_crank_timestamp = clock.unix_timestamp;
+    
+    emit!(EmissionReleased {
+        emission_schedule: emission_schedule.key(),
+        rewards_vault: emission_schedule.rewards_vault,
+        amount: releasable,
+        released_amount: emission_schedule.released_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Released {} DATA tokens ({}/{}) to {}", releasable, emission_schedule.released_amount, emission_schedule.total_amount(), emission_schedule.rewards_vault);
+    
+    Ok(())
+}
@@ -80,7 +80,7 @@ pub enum DataChainError {
     #[msg("Invalid token metadata")]
     InvalidTokenMetadata,
     
-    #[msg("Token minting cooldown active")]
+    #[msg("Token emission allowance exhausted for the current window")]
     MintingCooldown,
     
     #[msg("Token maximum supply reached")]
@@ -94,4 +94,109 @@ pub enum DataChainError {
     
     #[msg("System error")]
     SystemError,
+    
+    #[msg("No vested tokens available to claim")]
+    NothingToClaim,
+    
+    #[msg("Vesting schedule is not revocable")]
+    VestingNotRevocable,
+    
+    #[msg("Vesting schedule already revoked")]
+    VestingAlreadyRevoked,
+    
+    #[msg("Stake amount below minimum")]
+    StakeBelowMinimum,
+    
+    #[msg("Invalid staking lockup tier")]
+    InvalidLockupTier,
+    
+    #[msg("Stake is still locked")]
+    StakeLocked,
+    
+    #[msg("Signer is not a member of the multisig")]
+    NotMultisigSigner,
+    
+    #[msg("Mint proposal already approved by this signer")]
+    MintProposalAlreadyApproved,
+    
+    #[msg("Mint proposal approval threshold not reached")]
+    MintThresholdNotReached,
+    
+    #[msg("Mint proposal already executed")]
+    MintProposalExecuted,
+    
+    #[msg("Protocol subsystem is paused")]
+    ProtocolPaused,
+    
+    #[msg("Mint amount exceeds the remaining emission allowance")]
+    EmissionCapExceeded,
+    
+    #[msg("Memo is empty or too long")]
+    InvalidMemo,
+    
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    
+    #[msg("Airdrop already claimed")]
+    AirdropAlreadyClaimed,
+    
+    #[msg("Airdrop has expired")]
+    AirdropExpired,
+    
+    #[msg("Airdrop has not expired yet")]
+    AirdropNotExpired,
+    
+    #[msg("Wallet is blocked by compliance")]
+    WalletBlocked,
+    
+    #[msg("Blocklist is full")]
+    BlocklistFull,
+    
+    #[msg("Dataset is not listed for sale")]
+    DatasetNotListed,
+    
+    #[msg("Instruction does not match the dataset pricing mode")]
+    InvalidPricingMode,
+    
+    #[msg("Subscription has expired")]
+    SubscriptionExpired,
+    
+    #[msg("Escrow window is still open")]
+    EscrowWindowOpen,
+    
+    #[msg("Escrow window has closed")]
+    EscrowWindowClosed,
+    
+    #[msg("Escrow is not in the required state")]
+    InvalidEscrowStatus,
+    
+    #[msg("Buyer has not set an encryption key")]
+    EncryptionKeyNotSet,
+    
+    #[msg("Dataset access key has not been delivered")]
+    AccessKeyNotDelivered,
+    
+    #[msg("Purchase does not grant access to this dataset version")]
+    VersionNotPurchased,
+    
+    #[msg("Revenue split shares or recipients are invalid")]
+    InvalidRevenueSplit,
+    
+    #[msg("Bundle members are invalid")]
+    InvalidBundle,
+    
+    #[msg("Coupon has expired")]
+    CouponExpired,
+    
+    #[msg("Coupon has no redemptions left")]
+    CouponExhausted,
+    
+    #[msg("Coupon is not valid for this buyer")]
+    CouponNotAllowed,
+    
+    #[msg("Dataset access key has already been delivered")]
+    AccessKeyAlreadyDelivered,
+    
+    #[msg("Bundles cannot be purchased while the marketplace escrows payments")]
+    BundleEscrowUnsupported,
 } 
\ No newline at end of file
@@ -0,0 +1,566 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::data_marketplace::{self, Dataset, MarketplaceConfig, PurchaseRecord, PurchaseTerms, PaymentAccounts, PaymentSplit};
+use crate::coupon::Coupon;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED};
+use crate::compliance::{Blocklist, BLOCKLIST_SEED};
+use crate::revenue_split::{RevenueSplit, SplitRecipient, MAX_SPLIT_RECIPIENTS, REVENUE_SPLIT_VERSION};
+use crate::events::*;
+
+// Constants for purchase escrow
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const ESCROW_AUTHORITY_SEED: &[u8] = b"escrow_authority";
+pub const ESCROW_VAULT_SEED: &[u8] = b"escrow_vault";
+pub const MAX_ESCROW_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const ESCROW_SPACE: usize = 8 + std::mem::size_of::<Escrow>() + (MAX_SPLIT_RECIPIENTS * std::mem::size_of::<SplitRecipient>());
+
+// Escrow status
+pub enum EscrowStatus {
+    Held = 0,
+    Disputed = 1,
+}
+
+// Payment held for a purchase until delivery is confirmed or the dispute window ends
+#[account]
+pub struct Escrow {
+    // Marketplace whose vault holds the payment
+    pub marketplace_config: Pubkey,
+    
+    // Purchase the payment is for
+    pub purchase_record: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub payment_mint: Pubkey,
+    
+    // Amount held in the escrow vault
+    pub amount: u64,
+    
+    // Shares paid out on release, fixed at purchase
+    pub platform_fee: u64,
+    pub staking_amount: u64,
+    pub seller_amount: u64,
+    
+    // Revenue split recipients at purchase, empty when the seller is paid directly
+    pub split_recipients: Vec<SplitRecipient>,
+    
+    // Subscription time a renewal paid for, zero for a purchase
+    pub renewal_period: i64,
+    
+    // Terms of the lapsed record a purchase bought again, restored on a refund
+    pub prior_terms: Option<PurchaseTerms>,
+    
+    // Coupon redeemed on the purchase, given back on a refund
+    pub coupon: Option<Pubkey>,
+    
+    // Escrow status and end of the dispute window
+    pub status: u8,
+    pub release_at: i64,
+    
+    // Creation timestamp
+    pub created_at: i64,
+    
+    // Bump of the escrow PDA
+    pub bump: u8,
+}
+
+impl Escrow {
+    pub fn split(&self) -> PaymentSplit {
+        PaymentSplit {
+            platform_fee: self.platform_fee,
+            staking_amount: self.staking_amount,
+            seller_amount: self.seller_amount,
+        }
+    }
+    
+    // Revenue split the payment was grossed up over, so later changes to the split do not apply
+    pub fn revenue_split(&self) -> Option<RevenueSplit> {
+        if self.split_recipients.is_empty() {
+            return None;
+        }
+        
+        Some(RevenueSplit {
+            dataset: self.dataset,
+            recipients: self.split_recipients.clone(),
+            updated_at: self.created_at,
+            bump: 0,
+            version: REVENUE_SPLIT_VERSION,
+        })
+    }
+}
+
+// Address of the escrow holding payment for a purchase
+pub fn find_escrow_address(purchase_record: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, purchase_record.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Context for setting the escrow window
+#[derive(Accounts)]
+pub struct SetEscrowPeriod<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = marketplace_config.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+}
+
+// Context for creating the escrow vault of a payment mint
+#[derive(Accounts)]
+pub struct InitializeEscrowVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        constraint = marketplace_config.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    #[account(
+        constraint = payment_mint.key() == marketplace_config.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: PDA signing for the escrow vaults
+    #[account(
+        seeds = [ESCROW_AUTHORITY_SEED, marketplace_config.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [ESCROW_VAULT_SEED, marketplace_config.key().as_ref(), payment_mint.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Context for the buyer disputing a purchase
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.purchase_record.as_ref()],
+        bump = escrow.bump,
+        constraint = escrow.buyer == buyer.key() @ DataChainError::Unauthorized,
+        constraint = escrow.status == EscrowStatus::Held as u8 @ DataChainError::InvalidEscrowStatus
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        constraint = purchase_record.key() == escrow.purchase_record @ DataChainError::InvalidParameters
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for paying escrowed funds out to the seller, platform and stakers
+#[derive(Accounts)]
+pub struct SettleEscrow<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [ESCROW_SEED, escrow.purchase_record.as_ref()],
+        bump = escrow.bump,
+        constraint = escrow.marketplace_config == marketplace_config.key() @ DataChainError::InvalidParameters
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        constraint = purchase_record.key() == escrow.purchase_record @ DataChainError::InvalidParameters
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+    
+    /// CHECK: Buyer who funded the escrow account, refunded its rent
+    #[account(
+        mut,
+        constraint = buyer.key() == escrow.buyer @ DataChainError::InvalidParameters
+    )]
+    pub buyer: AccountInfo<'info>,
+    
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    /// CHECK: PDA signing for the escrow vaults
+    #[account(
+        seeds = [ESCROW_AUTHORITY_SEED, marketplace_config.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED, marketplace_config.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        constraint = payment_mint.key() == escrow.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = owner_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == escrow.seller @ DataChainError::Unauthorized
+    )]
+    pub owner_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = platform_fee_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = platform_fee_token.owner == marketplace_config.platform_fee_recipient @ DataChainError::Unauthorized
+    )]
+    pub platform_fee_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = staking_reward_vault.key() == marketplace_config.staking_reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub staking_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BLOCKLIST_SEED],
+        bump = blocklist.bump,
+        constraint = blocklist.token_info == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = !blocklist.is_blocked(&escrow.seller) @ DataChainError::WalletBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for the marketplace authority settling a dispute
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = marketplace_config.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub marketplace_config: Account<'info, MarketplaceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.purchase_record.as_ref()],
+        bump = escrow.bump,
+        constraint = escrow.marketplace_config == marketplace_config.key() @ DataChainError::InvalidParameters,
+        constraint = escrow.status == EscrowStatus::Disputed as u8 @ DataChainError::InvalidEscrowStatus
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        constraint = purchase_record.key() == escrow.purchase_record @ DataChainError::InvalidParameters
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+    
+    #[account(
+        mut,
+        constraint = dataset.key() == escrow.dataset @ DataChainError::InvalidParameters
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    /// CHECK: Buyer who funded the escrow and purchase record accounts
+    #[account(
+        mut,
+        constraint = buyer.key() == escrow.buyer @ DataChainError::InvalidParameters
+    )]
+    pub buyer: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token.mint == payment_mint.key() @ DataChainError::InvalidParameters,
+        constraint = buyer_token.owner == escrow.buyer @ DataChainError::Unauthorized
+    )]
+    pub buyer_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA signing for the escrow vaults
+    #[account(
+        seeds = [ESCROW_AUTHORITY_SEED, marketplace_config.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ESCROW_VAULT_SEED, marketplace_config.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        constraint = payment_mint.key() == escrow.payment_mint @ DataChainError::InvalidParameters
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: Coupon redeemed on the purchase, checked against the escrow; it may have been closed since
+    #[account(
+        mut,
+        constraint = escrow.coupon == Some(coupon.key()) @ DataChainError::InvalidParameters
+    )]
+    pub coupon: Option<AccountInfo<'info>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of escrow functions
+pub fn set_escrow_period(
+    ctx: Context<SetEscrowPeriod>,
+    escrow_period: i64,
+) -> Result<()> {
+    if !(0..=MAX_ESCROW_PERIOD).contains(&escrow_period) {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let marketplace_config = &mut ctx.accounts.marketplace_config;
+    
+    marketplace_config.escrow_period = escrow_period;
+    
+    emit!(EscrowPeriodSet {
+        marketplace_config: marketplace_config.key(),
+        escrow_period,
+    });
+    
+    msg!("Purchase escrow period set to {} seconds", escrow_period);
+    
+    Ok(())
+}
+
+pub fn initialize_escrow_vault(
+    ctx: Context<InitializeEscrowVault>,
+) -> Result<()> {
+    emit!(EscrowVaultInitialized {
+        marketplace_config: ctx.accounts.marketplace_config.key(),
+        escrow_vault: ctx.accounts.escrow_vault.key(),
+        payment_mint: ctx.accounts.payment_mint.key(),
+    });
+    
+    msg!("Escrow vault initialized for mint {}", ctx.accounts.payment_mint.key());
+    
+    Ok(())
+}
+
+// Disputes can be opened while the escrow window is open, or at any time until the key is delivered
+pub fn open_dispute(
+    ctx: Context<OpenDispute>,
+) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let clock = &ctx.accounts.clock;
+    
+    if clock.unix_timestamp >= escrow.release_at && ctx.accounts.purchase_record.is_delivered() {
+        return err!(DataChainError::EscrowWindowClosed);
+    }
+    
+    escrow.status = EscrowStatus::Disputed as u8;
+    
+    emit!(DisputeOpened {
+        escrow: escrow.key(),
+        dataset: escrow.dataset,
+        buyer: escrow.buyer,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dispute opened on escrow {}", escrow.key());
+    
+    Ok(())
+}
+
+// The buyer can release the payment at any time, including to withdraw a dispute
+pub fn confirm_delivery<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleEscrow<'info>>,
+) -> Result<()> {
+    if ctx.accounts.caller.key() != ctx.accounts.escrow.buyer {
+        return err!(DataChainError::Unauthorized);
+    }
+    
+    settle_escrow(ctx)
+}
+
+// Anyone can release an undisputed payment once the key is delivered and the escrow window has ended
+pub fn release_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleEscrow<'info>>,
+) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    
+    if escrow.status != EscrowStatus::Held as u8 {
+        return err!(DataChainError::InvalidEscrowStatus);
+    }
+    
+    if !ctx.accounts.purchase_record.is_delivered() {
+        return err!(DataChainError::AccessKeyNotDelivered);
+    }
+    
+    if ctx.accounts.clock.unix_timestamp < escrow.release_at {
+        return err!(DataChainError::EscrowWindowOpen);
+    }
+    
+    settle_escrow(ctx)
+}
+
+// Pay the escrowed shares out of the vault, across the revenue split recorded at purchase; the escrow account is closed to the buyer
+fn settle_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleEscrow<'info>>,
+) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let marketplace_config_key = ctx.accounts.marketplace_config.key();
+    let escrow_authority_bump = *ctx.bumps.get("escrow_authority").ok_or(DataChainError::SystemError)?;
+    let seeds = &[
+        ESCROW_AUTHORITY_SEED,
+        marketplace_config_key.as_ref(),
+        &[escrow_authority_bump],
+    ];
+    
+    let split = escrow.split();
+    let revenue_split = escrow.revenue_split();
+    PaymentAccounts {
+        payer: &ctx.accounts.escrow_authority,
+        payer_token: &ctx.accounts.escrow_vault,
+        owner_token: Some(&ctx.accounts.owner_token),
+        platform_fee_token: &ctx.accounts.platform_fee_token,
+        staking_reward_vault: ctx.accounts.staking_reward_vault.as_ref(),
+        payment_mint: &ctx.accounts.payment_mint,
+        token_program: &ctx.accounts.token_program,
+        revenue_split: revenue_split.as_ref(),
+        recipient_tokens: ctx.remaining_accounts,
+        signer_seeds: &[&seeds[..]],
+    }.pay(&split)?;
+    
+    emit!(EscrowReleased {
+        escrow: escrow.key(),
+        dataset: escrow.dataset,
+        seller: escrow.seller,
+        amount: escrow.amount,
+        platform_fee: split.platform_fee,
+        staking_fee: split.staking_amount,
+        seller_amount: split.seller_amount,
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+    
+    msg!("Escrow released: {}", escrow.key());
+    
+    Ok(())
+}
+
+// A refund returns the escrowed amount and revokes the access it paid for; otherwise the payment becomes releasable
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    refund_buyer: bool,
+) -> Result<()> {
+    let clock = &ctx.accounts.clock;
+    let escrow_key = ctx.accounts.escrow.key();
+    let amount = ctx.accounts.escrow.amount;
+    
+    if refund_buyer {
+        let marketplace_config_key = ctx.accounts.marketplace_config.key();
+        let escrow_authority_bump = *ctx.bumps.get("escrow_authority").ok_or(DataChainError::SystemError)?;
+        let seeds = &[
+            ESCROW_AUTHORITY_SEED,
+            marketplace_config_key.as_ref(),
+            &[escrow_authority_bump],
+        ];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow_vault.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+            to: ctx.accounts.buyer_token.to_account_info(),
+            authority: ctx.accounts.escrow_authority.to_account_info(),
+        };
+        
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            amount,
+            ctx.accounts.payment_mint.decimals,
+        )?;
+        
+        let escrow = &ctx.accounts.escrow;
+        let price = escrow.platform_fee.checked_add(escrow.seller_amount).ok_or(DataChainError::InvalidParameters)?;
+        let renewal_period = escrow.renewal_period;
+        let prior_terms = escrow.prior_terms;
+        let dataset = &mut ctx.accounts.dataset;
+        let marketplace_config = &mut ctx.accounts.marketplace_config;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        
+        marketplace_config.total_volume = marketplace_config.total_volume.saturating_sub(price);
+        
+        // Give the redemption back to the coupon, unless it has been closed since
+        if escrow.coupon.is_some() {
+            let coupon_info = ctx.accounts.coupon.as_ref().ok_or(DataChainError::InvalidParameters)?;
+            if *coupon_info.owner == crate::ID && !coupon_info.data_is_empty() {
+                let mut coupon = Account::<Coupon>::try_from(coupon_info)?.into_inner();
+                coupon.redemptions = coupon.redemptions.saturating_sub(1);
+                data_marketplace::write_account(coupon_info, &coupon)?;
+            }
+        }
+        
+        if renewal_period > 0 {
+            // A refunded renewal only takes back the period it paid for
+            purchase_record.expires_at = purchase_record.expires_at.map(|expires_at| expires_at.saturating_sub(renewal_period));
+            purchase_record.price_paid = purchase_record.price_paid.saturating_sub(price);
+        } else {
+            // Reverse the purchase stats and revoke access
+            dataset.purchases = dataset.purchases.saturating_sub(1);
+            marketplace_config.total_purchases = marketplace_config.total_purchases.saturating_sub(1);
+            
+            // A lapsed record bought again goes back to its earlier terms, keeping its rating and key delivery
+            match prior_terms {
+                Some(prior_terms) => purchase_record.restore_terms(&prior_terms),
+                None => purchase_record.close(buyer.clone())?,
+            }
+        }
+        
+        ctx.accounts.escrow.close(buyer)?;
+    } else {
+        let escrow = &mut ctx.accounts.escrow;
+        
+        escrow.status = EscrowStatus::Held as u8;
+        escrow.release_at = clock.unix_timestamp;
+    }
+    
+    emit!(DisputeResolved {
+        escrow: escrow_key,
+        dataset: ctx.accounts.escrow.dataset,
+        buyer: ctx.accounts.escrow.buyer,
+        refunded: refund_buyer,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Dispute resolved on escrow {}: {}", escrow_key, if refund_buyer { "refunded" } else { "released" });
+    
+    Ok(())
+}
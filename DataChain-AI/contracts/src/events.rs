@@ -0,0 +1,635 @@
+use anchor_lang::prelude::*;
+
+// Events emitted by every state-changing instruction.
+// Indexers decode these by layout: only ever append fields, never reorder or remove them.
+
+// DATA token events
+#[event]
+pub struct TokenInitialized {
+    pub token_info: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenMinted {
+    pub token_info: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub current_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionPolicyUpdated {
+    pub token_info: Pubkey,
+    pub emission_window: i64,
+    pub emission_cap: u64,
+}
+
+#[event]
+pub struct TokensTransferred {
+    pub mint: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub memo: Option<String>,
+}
+
+#[event]
+pub struct TokenMetadataUpdated {
+    pub token_info: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub token_info: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub current_supply: u64,
+    pub total_burned: u64,
+}
+
+#[event]
+pub struct BurnVaultInitialized {
+    pub token_info: Pubkey,
+    pub burn_vault: Pubkey,
+}
+
+#[event]
+pub struct TreasuryBurnExecuted {
+    pub token_info: Pubkey,
+    pub amount: u64,
+    pub current_supply: u64,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferRequested {
+    pub token_info: Pubkey,
+    pub current_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub expiry_timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub token_info: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub token_info: Pubkey,
+    pub authority: Pubkey,
+}
+
+// Mint multisig events
+#[event]
+pub struct MintMultisigCreated {
+    pub token_info: Pubkey,
+    pub multisig: Pubkey,
+    pub threshold: u8,
+    pub signer_count: u8,
+}
+
+#[event]
+pub struct MintProposed {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MintApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approval_count: u8,
+}
+
+#[event]
+pub struct MintProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub current_supply: u64,
+    pub timestamp: i64,
+}
+
+// Vesting events
+#[event]
+pub struct VestingScheduleCreated {
+    pub vesting_schedule: Pubkey,
+    pub authority: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_timestamp: i64,
+    pub duration: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestedTokensClaimed {
+    pub vesting_schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub vesting_schedule: Pubkey,
+    pub vested_amount: u64,
+    pub unvested_amount: u64,
+}
+
+// Airdrop events
+#[event]
+pub struct AirdropCreated {
+    pub distributor: Pubkey,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_recipients: u64,
+    pub expiry_timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AirdropClawedBack {
+    pub distributor: Pubkey,
+    pub amount: u64,
+}
+
+// Emission schedule events
+#[event]
+pub struct EmissionScheduleCreated {
+    pub emission_schedule: Pubkey,
+    pub rewards_vault: Pubkey,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct EmissionReleased {
+    pub emission_schedule: Pubkey,
+    pub rewards_vault: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+    pub timestamp: i64,
+}
+
+// Compliance events
+#[event]
+pub struct FreezeAuthoritySet {
+    pub token_info: Pubkey,
+    pub freeze_authority: Pubkey,
+}
+
+#[event]
+pub struct TokenAccountFrozen {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct TokenAccountThawed {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct BlocklistInitialized {
+    pub blocklist: Pubkey,
+    pub token_info: Pubkey,
+}
+
+#[event]
+pub struct BlocklistWalletAdded {
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct BlocklistWalletRemoved {
+    pub wallet: Pubkey,
+}
+
+// Protocol events
+#[event]
+pub struct ProtocolStateInitialized {
+    pub authority: Pubkey,
+    pub guardian: Pubkey,
+    pub token_info: Pubkey,
+}
+
+#[event]
+pub struct SubsystemsPaused {
+    pub signer: Pubkey,
+    pub flags: u8,
+    pub paused: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubsystemsUnpaused {
+    pub authority: Pubkey,
+    pub flags: u8,
+    pub paused: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianSet {
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct ProtocolAuthorityTransferRequested {
+    pub authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct ProtocolAuthorityTransferred {
+    pub authority: Pubkey,
+}
+
+// Data marketplace events
+#[event]
+pub struct MarketplaceInitialized {
+    pub marketplace_config: Pubkey,
+    pub authority: Pubkey,
+    pub platform_fee_percentage: u8,
+    pub platform_fee_recipient: Pubkey,
+    pub payment_mint: Pubkey,
+}
+
+#[event]
+pub struct StakingFeeShareSet {
+    pub marketplace_config: Pubkey,
+    pub stake_pool: Pubkey,
+    pub staking_fee_percentage: u8,
+    pub staking_reward_vault: Pubkey,
+}
+
+#[event]
+pub struct DatasetRegistered {
+    pub dataset: Pubkey,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetUpdated {
+    pub dataset: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetPriceSet {
+    pub dataset: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetListingChanged {
+    pub dataset: Pubkey,
+    pub is_listed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetPricingSet {
+    pub dataset: Pubkey,
+    pub pricing_mode: u8,
+    pub subscription_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetPurchased {
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetSubscribed {
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub seller_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionRenewed {
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub seller_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetVersionPublished {
+    pub dataset: Pubkey,
+    pub dataset_version: Pubkey,
+    pub version_number: u32,
+    pub content_hash: [u8; 32],
+    pub byte_size: u64,
+    pub schema_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetAccessScopeSet {
+    pub dataset: Pubkey,
+    pub access_scope: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueSplitSet {
+    pub dataset: Pubkey,
+    pub revenue_split: Pubkey,
+    pub recipient_count: u8,
+}
+
+#[event]
+pub struct RevenueSplitRemoved {
+    pub dataset: Pubkey,
+    pub revenue_split: Pubkey,
+}
+
+#[event]
+pub struct DeliveryAuthoritySet {
+    pub dataset: Pubkey,
+    pub delivery_authority: Pubkey,
+}
+
+#[event]
+pub struct EncryptionKeySet {
+    pub purchase_record: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub encryption_key: [u8; 32],
+}
+
+#[event]
+pub struct AccessKeyDelivered {
+    pub purchase_record: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub delivered_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DatasetRated {
+    pub dataset: Pubkey,
+    pub rater: Pubkey,
+    pub rating: u8,
+    pub timestamp: i64,
+}
+
+// Coupon events
+#[event]
+pub struct CouponCreated {
+    pub coupon: Pubkey,
+    pub dataset: Pubkey,
+    pub discount_type: u8,
+    pub discount_value: u64,
+    pub max_redemptions: u32,
+    pub expires_at: Option<i64>,
+    pub allowed_buyer: Option<Pubkey>,
+}
+
+#[event]
+pub struct CouponRedeemed {
+    pub coupon: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub list_price: u64,
+    pub price_paid: u64,
+    pub redemptions: u32,
+}
+
+#[event]
+pub struct CouponClosed {
+    pub coupon: Pubkey,
+    pub dataset: Pubkey,
+    pub redemptions: u32,
+}
+
+// Dataset bundle events
+#[event]
+pub struct BundleCreated {
+    pub bundle: Pubkey,
+    pub creator: Pubkey,
+    pub price: u64,
+    pub dataset_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BundlePurchased {
+    pub bundle: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+}
+
+// Purchase escrow events
+#[event]
+pub struct EscrowPeriodSet {
+    pub marketplace_config: Pubkey,
+    pub escrow_period: i64,
+}
+
+#[event]
+pub struct EscrowVaultInitialized {
+    pub marketplace_config: Pubkey,
+    pub escrow_vault: Pubkey,
+    pub payment_mint: Pubkey,
+}
+
+#[event]
+pub struct PaymentEscrowed {
+    pub escrow: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub release_at: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub escrow: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowReleased {
+    pub escrow: Pubkey,
+    pub dataset: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub escrow: Pubkey,
+    pub dataset: Pubkey,
+    pub buyer: Pubkey,
+    pub refunded: bool,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// AI model marketplace events
+#[event]
+pub struct ModelRegistered {
+    pub model: Pubkey,
+    pub owner: Pubkey,
+    pub price_per_query: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ModelUsed {
+    pub model: Pubkey,
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub platform_fee: u64,
+    pub staking_fee: u64,
+    pub owner_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ModelRated {
+    pub model: Pubkey,
+    pub rater: Pubkey,
+    pub rating: u8,
+    pub timestamp: i64,
+}
+
+// Governance events
+#[event]
+pub struct GovernanceInitialized {
+    pub governance_config: Pubkey,
+    pub authority: Pubkey,
+    pub governance_token: Pubkey,
+    pub voting_period: i64,
+    pub quorum_percentage: u8,
+    pub approval_threshold_percentage: u8,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub creator: Pubkey,
+    pub proposal_type: u8,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote: bool,
+    pub weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed_at: i64,
+}
+
+// Staking events
+#[event]
+pub struct StakePoolInitialized {
+    pub stake_pool: Pubkey,
+    pub stake_mint: Pubkey,
+}
+
+#[event]
+pub struct Staked {
+    pub stake_pool: Pubkey,
+    pub stake_position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub weighted_amount: u64,
+    pub lockup_tier: u8,
+    pub unlock_at: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub stake_pool: Pubkey,
+    pub stake_position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub rewards: u64,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub stake_pool: Pubkey,
+    pub stake_position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
@@ -1,13 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use solana_program::{
     program::invoke_signed,
     sysvar::clock::Clock,
 };
 use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_VOTING};
+use crate::data_token::{self, TokenInfo};
+use crate::events::*;
 
 // Constants for governance system
 pub const GOVERNANCE_VERSION: u8 = 1;
+pub const GOVERNANCE_CONFIG_SEED: &[u8] = b"governance_config";
+pub const VOTE_RECORD_SEED: &[u8] = b"vote_record";
 pub const VOTING_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days in seconds
 pub const EXECUTION_DELAY: i64 = 2 * 24 * 60 * 60; // 2 days in seconds after voting ends
 pub const QUORUM_PERCENTAGE: u8 = 10; // 10% of total supply must vote for proposal to be valid
@@ -25,6 +30,14 @@ pub enum ProposalType {
     RemoveFeature = 3,
     FundProject = 4,
     Other = 5,
+    UpdateEmissionPolicy = 6,
+}
+
+// Execution parameters of an UpdateEmissionPolicy proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EmissionPolicyParams {
+    pub emission_window: i64,
+    pub emission_cap: u64,
 }
 
 // Proposal status
@@ -42,6 +55,9 @@ pub struct Proposal {
     // Proposal creator
     pub creator: Pubkey,
     
+    // Governance config the proposal is voted and executed under
+    pub governance_config: Pubkey,
+    
     // Proposal metadata
     pub title: String,
     pub description: String,
@@ -106,6 +122,9 @@ pub struct GovernanceConfig {
     pub total_proposals: u64,
     pub executed_proposals: u64,
     
+    // Bump of the governance config PDA
+    pub bump: u8,
+    
     // Version for future upgrades
     pub version: u8,
 }
@@ -130,9 +149,25 @@ pub struct CreateProposal<'info> {
     )]
     pub creator_token: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [GOVERNANCE_CONFIG_SEED, governance_config.governance_token.as_ref()],
+        bump = governance_config.bump
+    )]
     pub governance_config: Account<'info, GovernanceConfig>,
     
+    #[account(
+        constraint = governance_token.key() == governance_config.governance_token @ DataChainError::InvalidParameters
+    )]
+    pub governance_token: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_VOTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub clock: Sysvar<'info, Clock>,
@@ -146,15 +181,19 @@ pub struct Vote<'info> {
     
     #[account(
         mut,
+        constraint = proposal.governance_config == governance_config.key() @ DataChainError::InvalidParameters,
         constraint = proposal.status == ProposalStatus::Active as u8 @ DataChainError::ProposalVotingEnded,
         constraint = proposal.voting_ends_at > clock.unix_timestamp @ DataChainError::ProposalVotingEnded
     )]
     pub proposal: Account<'info, Proposal>,
     
+    // One vote record per voter and proposal
     #[account(
         init,
         payer = voter,
         space = 8 + std::mem::size_of::<Vote>(),
+        seeds = [VOTE_RECORD_SEED, proposal.key().as_ref(), voter.key().as_ref()],
+        bump
     )]
     pub vote_record: Account<'info, Vote>,
     
@@ -167,6 +206,13 @@ pub struct Vote<'info> {
     
     pub governance_config: Account<'info, GovernanceConfig>,
     
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_VOTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub clock: Sysvar<'info, Clock>,
@@ -180,14 +226,29 @@ pub struct ExecuteProposal<'info> {
     
     #[account(
         mut,
-        constraint = proposal.status == ProposalStatus::Approved as u8 @ DataChainError::ProposalRejected,
+        constraint = proposal.governance_config == governance_config.key() @ DataChainError::InvalidParameters,
+        constraint = proposal.status == ProposalStatus::Active as u8 || proposal.status == ProposalStatus::Approved as u8 @ DataChainError::ProposalRejected,
         constraint = proposal.voting_ends_at + governance_config.execution_delay < clock.unix_timestamp @ DataChainError::InvalidTimestamp
     )]
     pub proposal: Account<'info, Proposal>,
     
+    #[account(mut)]
     pub governance_config: Account<'info, GovernanceConfig>,
     
-    // Note: Additional accounts required for execution would be passed based on proposal type
+    // Canonical token info, required by UpdateEmissionPolicy proposals
+    #[account(
+        mut,
+        constraint = token_info.key() == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = token_info.mint == governance_config.governance_token @ DataChainError::InvalidParameters
+    )]
+    pub token_info: Option<Account<'info, TokenInfo>>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_VOTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
     
     pub clock: Sysvar<'info, Clock>,
 }
@@ -222,23 +283,30 @@ pub fn create_proposal(
         }
     }
     
-    if proposal_type > ProposalType::Other as u8 {
+    if proposal_type > ProposalType::UpdateEmissionPolicy as u8 {
         return err!(DataChainError::InvalidProposalType);
     }
     
+    // Emission policy proposals must carry a valid policy so voters approve exactly what executes
+    if proposal_type == ProposalType::UpdateEmissionPolicy as u8 {
+        let policy = emission_policy_params(&execution_params)?;
+        data_token::validate_emission_policy(policy.emission_window, policy.emission_cap)?;
+    }
+    
     let proposal = &mut ctx.accounts.proposal;
     let governance_config = &mut ctx.accounts.governance_config;
     let clock = &ctx.accounts.clock;
     
     // Initialize proposal
     proposal.creator = ctx.accounts.creator.key();
+    proposal.governance_config = governance_config.key();
     proposal.title = title;
     proposal.description = description;
     proposal.proposal_type = proposal_type;
     proposal.link = link;
     proposal.yes_votes = 0;
     proposal.no_votes = 0;
-    proposal.total_eligible_votes = 10_000_000_000; // This should be fetched from token supply
+    proposal.total_eligible_votes = ctx.accounts.governance_token.supply;
     proposal.execution_params = execution_params;
     proposal.created_at = clock.unix_timestamp;
     proposal.voting_ends_at = clock.unix_timestamp + governance_config.voting_period;
@@ -249,6 +317,13 @@ pub fn create_proposal(
     // Update governance stats
     governance_config.total_proposals = governance_config.total_proposals.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        creator: proposal.creator,
+        proposal_type: proposal.proposal_type,
+        voting_ends_at: proposal.voting_ends_at,
+    });
+    
     msg!("Proposal created: {}", proposal.title);
     
     Ok(())
@@ -286,6 +361,14 @@ pub fn vote(
         proposal.no_votes = proposal.no_votes.checked_add(vote_weight).ok_or(DataChainError::InvalidParameters)?;
     }
     
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        voter: vote_record.voter,
+        vote: vote_value,
+        weight: vote_weight,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!("Vote cast on proposal: {}", proposal.title);
     
     Ok(())
@@ -328,11 +411,13 @@ pub fn execute_proposal(
         return err!(DataChainError::ProposalRejected);
     }
     
-    // Execute the proposal logic based on proposal type
-    // This would typically involve calling other functions or programs
-    // based on the execution_params
+    // Apply proposals with on-chain effects; other types are only marked as executed
+    if proposal.proposal_type == ProposalType::UpdateEmissionPolicy as u8 {
+        let policy = emission_policy_params(&proposal.execution_params)?;
+        let token_info = ctx.accounts.token_info.as_mut().ok_or(DataChainError::InvalidParameters)?;
+        data_token::apply_emission_policy(token_info, policy.emission_window, policy.emission_cap, clock.unix_timestamp)?;
+    }
     
-    // For simplicity, we just mark it as executed
     proposal.status = ProposalStatus::Executed as u8;
     proposal.executed_at = Some(clock.unix_timestamp);
     
@@ -340,11 +425,24 @@ pub fn execute_proposal(
     let governance_config = &mut ctx.accounts.governance_config;
     governance_config.executed_proposals = governance_config.executed_proposals.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
     
+    emit!(ProposalExecuted {
+        proposal: proposal.key(),
+        yes_votes: proposal.yes_votes,
+        no_votes: proposal.no_votes,
+        executed_at: clock.unix_timestamp,
+    });
+    
     msg!("Proposal executed: {}", proposal.title);
     
     Ok(())
 }
 
+// Decode the emission policy carried by a proposal's execution parameters
+fn emission_policy_params(execution_params: &Option<Vec<u8>>) -> Result<EmissionPolicyParams> {
+    let params = execution_params.as_deref().ok_or(DataChainError::InvalidParameters)?;
+    EmissionPolicyParams::try_from_slice(params).map_err(|_| error!(DataChainError::InvalidParameters))
+}
+
 // Initialize governance configuration
 pub fn initialize_governance(
     ctx: Context<InitializeGovernance>,
@@ -372,13 +470,24 @@ pub fn initialize_governance(
     governance_config.approval_threshold_percentage = approval_threshold_percentage;
     governance_config.total_proposals = 0;
     governance_config.executed_proposals = 0;
+    governance_config.bump = *ctx.bumps.get("governance_config").ok_or(DataChainError::SystemError)?;
     governance_config.version = GOVERNANCE_VERSION;
     
+    emit!(GovernanceInitialized {
+        governance_config: governance_config.key(),
+        authority: governance_config.authority,
+        governance_token: governance_config.governance_token,
+        voting_period,
+        quorum_percentage,
+        approval_threshold_percentage,
+    });
+    
     msg!("Governance system initialized");
     
     Ok(())
 }
 
+// One governance config per token, created by the authority of the canonical token info
 #[derive(Accounts)]
 pub struct InitializeGovernance<'info> {
     #[account(mut)]
@@ -386,10 +495,25 @@ pub struct InitializeGovernance<'info> {
     
     pub governance_token: Account<'info, Mint>,
     
+    #[account(
+        constraint = token_info.key() == protocol_state.token_info @ DataChainError::InvalidParameters,
+        constraint = token_info.mint == governance_token.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
     #[account(
         init,
         payer = authority,
         space = 8 + std::mem::size_of::<GovernanceConfig>(),
+        seeds = [GOVERNANCE_CONFIG_SEED, governance_token.key().as_ref()],
+        bump
     )]
     pub governance_config: Account<'info, GovernanceConfig>,
     
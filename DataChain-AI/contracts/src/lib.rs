@@ -11,6 +11,18 @@ pub mod data_token;
 pub mod data_marketplace;
 pub mod ai_models;
 pub mod governance;
+pub mod vesting;
+pub mod staking;
+pub mod multisig;
+pub mod protocol;
+pub mod airdrop;
+pub mod emission;
+pub mod compliance;
+pub mod escrow;
+pub mod revenue_split;
+pub mod bundle;
+pub mod coupon;
+pub mod events;
 
 // Re-export key components
 pub use errors::*;
@@ -18,6 +30,18 @@ pub use data_token::*;
 pub use data_marketplace::*;
 pub use ai_models::*;
 pub use governance::*;
+pub use vesting::*;
+pub use staking::*;
+pub use multisig::*;
+pub use protocol::*;
+pub use airdrop::*;
+pub use emission::*;
+pub use compliance::*;
+pub use escrow::*;
+pub use revenue_split::*;
+pub use bundle::*;
+pub use coupon::*;
+pub use events::*;
 
 declare_id!("DATAnKVv5pzRz2DMyNwFiZCsZbM4QSFTUVeD5uBZ9Bs");
 
@@ -26,6 +50,54 @@ declare_id!("DATAnKVv5pzRz2DMyNwFiZCsZbM4QSFTUVeD5uBZ9Bs");
 pub mod datachain_ai {
     use super::*;
     
+    // Emergency Controls
+    
+    /// Initialize the protocol state with its guardian (program upgrade authority only)
+    pub fn initialize_protocol_state(
+        ctx: Context<InitializeProtocolState>,
+    ) -> Result<()> {
+        protocol::initialize_protocol_state(ctx)
+    }
+    
+    /// Pause one or more subsystems (guardian or authority)
+    pub fn pause(
+        ctx: Context<Pause>,
+        flags: u8,
+    ) -> Result<()> {
+        protocol::pause(ctx, flags)
+    }
+    
+    /// Unpause one or more subsystems (authority only)
+    pub fn unpause(
+        ctx: Context<ProtocolAdmin>,
+        flags: u8,
+    ) -> Result<()> {
+        protocol::unpause(ctx, flags)
+    }
+    
+    /// Rotate the protocol guardian
+    pub fn set_guardian(
+        ctx: Context<ProtocolAdmin>,
+        new_guardian: Pubkey,
+    ) -> Result<()> {
+        protocol::set_guardian(ctx, new_guardian)
+    }
+    
+    /// Propose a new protocol authority
+    pub fn request_protocol_authority_transfer(
+        ctx: Context<ProtocolAdmin>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        protocol::request_protocol_authority_transfer(ctx, new_authority)
+    }
+    
+    /// Accept a pending protocol authority transfer
+    pub fn accept_protocol_authority(
+        ctx: Context<AcceptProtocolAuthority>,
+    ) -> Result<()> {
+        protocol::accept_protocol_authority(ctx)
+    }
+    
     // DATA Token Management
     
     /// Initialize the DATA token
@@ -47,6 +119,13 @@ pub mod datachain_ai {
         data_token::mint_tokens(ctx, amount)
     }
     
+    /// Read how many DATA tokens can still be minted in the current emission window
+    pub fn get_emission_allowance(
+        ctx: Context<GetEmissionAllowance>,
+    ) -> Result<u64> {
+        data_token::get_emission_allowance(ctx)
+    }
+    
     /// Transfer DATA tokens with memo
     pub fn transfer_tokens(
         ctx: Context<TransferTokens>,
@@ -56,9 +135,218 @@ pub mod datachain_ai {
         data_token::transfer_tokens(ctx, amount, memo)
     }
     
+    /// Update the DATA token metadata
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        data_token::update_token_metadata(ctx, name, symbol, uri)
+    }
+    
+    /// Burn DATA tokens from the caller's account
+    pub fn burn_tokens(
+        ctx: Context<BurnTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        data_token::burn_tokens(ctx, amount)
+    }
+    
+    /// Create the treasury fee vault used for buyback-and-burn
+    pub fn initialize_burn_vault(
+        ctx: Context<InitializeBurnVault>,
+    ) -> Result<()> {
+        data_token::initialize_burn_vault(ctx)
+    }
+    
+    /// Burn DATA tokens held in the treasury fee vault
+    pub fn execute_burn(
+        ctx: Context<ExecuteBurn>,
+        amount: u64,
+    ) -> Result<()> {
+        data_token::execute_burn(ctx, amount)
+    }
+    
+    /// Request a transfer of the token authority
+    pub fn request_authority_transfer(
+        ctx: Context<RequestAuthorityTransfer>,
+    ) -> Result<()> {
+        data_token::request_authority_transfer(ctx)
+    }
+    
+    /// Accept a pending token authority transfer
+    pub fn accept_authority_transfer(
+        ctx: Context<AcceptAuthorityTransfer>,
+    ) -> Result<()> {
+        data_token::accept_authority_transfer(ctx)
+    }
+    
+    /// Cancel a pending token authority transfer
+    pub fn cancel_authority_transfer(
+        ctx: Context<CancelAuthorityTransfer>,
+    ) -> Result<()> {
+        data_token::cancel_authority_transfer(ctx)
+    }
+    
+    // Compliance
+    
+    /// Set the compliance freeze authority
+    pub fn set_freeze_authority(
+        ctx: Context<SetFreezeAuthority>,
+        new_freeze_authority: Pubkey,
+    ) -> Result<()> {
+        compliance::set_freeze_authority(ctx, new_freeze_authority)
+    }
+    
+    /// Freeze a DATA token account
+    pub fn freeze_account(
+        ctx: Context<FreezeTokenAccount>,
+    ) -> Result<()> {
+        compliance::freeze_account(ctx)
+    }
+    
+    /// Thaw a frozen DATA token account
+    pub fn thaw_account(
+        ctx: Context<FreezeTokenAccount>,
+    ) -> Result<()> {
+        compliance::thaw_account(ctx)
+    }
+    
+    /// Create the compliance blocklist
+    pub fn initialize_blocklist(
+        ctx: Context<InitializeBlocklist>,
+    ) -> Result<()> {
+        compliance::initialize_blocklist(ctx)
+    }
+    
+    /// Block a wallet from transfers and marketplace activity
+    pub fn add_to_blocklist(
+        ctx: Context<UpdateBlocklist>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        compliance::add_to_blocklist(ctx, wallet)
+    }
+    
+    /// Remove a wallet from the blocklist
+    pub fn remove_from_blocklist(
+        ctx: Context<UpdateBlocklist>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        compliance::remove_from_blocklist(ctx, wallet)
+    }
+    
+    // Mint Multisig
+    
+    /// Require M-of-N multisig approval for minting DATA
+    pub fn create_mint_multisig(
+        ctx: Context<CreateMintMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        multisig::create_mint_multisig(ctx, signers, threshold)
+    }
+    
+    /// Propose a multisig mint
+    pub fn propose_mint(
+        ctx: Context<ProposeMint>,
+        amount: u64,
+    ) -> Result<()> {
+        multisig::propose_mint(ctx, amount)
+    }
+    
+    /// Approve a pending multisig mint
+    pub fn approve_mint(
+        ctx: Context<ApproveMint>,
+    ) -> Result<()> {
+        multisig::approve_mint(ctx)
+    }
+    
+    /// Execute a multisig mint once the approval threshold is reached
+    pub fn execute_mint(
+        ctx: Context<ExecuteMint>,
+    ) -> Result<()> {
+        multisig::execute_mint(ctx)
+    }
+    
+    // Token Vesting
+    
+    /// Lock DATA in a vesting vault with a cliff and linear release, until minting moves to the multisig
+    pub fn create_vesting_schedule(
+        ctx: Context<CreateVestingSchedule>,
+        amount: u64,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        duration: i64,
+        revocable: bool,
+    ) -> Result<()> {
+        vesting::create_vesting_schedule(ctx, amount, start_timestamp, cliff_timestamp, duration, revocable)
+    }
+    
+    /// Claim vested DATA tokens
+    pub fn claim_vested(
+        ctx: Context<ClaimVested>,
+    ) -> Result<()> {
+        vesting::claim_vested(ctx)
+    }
+    
+    /// Revoke a revocable vesting schedule, returning unvested tokens
+    pub fn revoke_vesting(
+        ctx: Context<RevokeVesting>,
+    ) -> Result<()> {
+        vesting::revoke_vesting(ctx)
+    }
+    
+    // Airdrops
+    
+    /// Fund an airdrop vault and post the merkle root of its allocations
+    pub fn create_airdrop(
+        ctx: Context<CreateAirdrop>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_recipients: u64,
+        expiry_timestamp: i64,
+    ) -> Result<()> {
+        airdrop::create_airdrop(ctx, merkle_root, total_amount, num_recipients, expiry_timestamp)
+    }
+    
+    /// Claim an airdrop allocation with a merkle proof
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        airdrop::claim_airdrop(ctx, index, amount, proof)
+    }
+    
+    /// Return unclaimed airdrop tokens after expiry
+    pub fn clawback_airdrop(
+        ctx: Context<ClawbackAirdrop>,
+    ) -> Result<()> {
+        airdrop::clawback_airdrop(ctx)
+    }
+    
+    // Ecosystem Emissions
+    
+    /// Create a piecewise-linear emission schedule into a rewards vault, until minting moves to the multisig
+    pub fn create_emission_schedule(
+        ctx: Context<CreateEmissionSchedule>,
+        points: Vec<EmissionPoint>,
+    ) -> Result<()> {
+        emission::create_emission_schedule(ctx, points)
+    }
+    
+    /// Mint unlocked-but-unreleased emissions into the rewards vault
+    pub fn crank_emission(
+        ctx: Context<CrankEmission>,
+    ) -> Result<()> {
+        emission::crank_emission(ctx)
+    }
+    
     // Data Marketplace
     
-    /// Register a new dataset on the marketplace
+    /// Register a new dataset on the marketplace; `uri` is public and must point to encrypted data
     pub fn register_dataset(
         ctx: Context<RegisterDataset>,
         name: String,
@@ -72,13 +360,110 @@ pub mod datachain_ai {
     }
     
     /// Purchase access to a dataset
-    pub fn purchase_dataset(
-        ctx: Context<PurchaseDataset>,
+    pub fn purchase_dataset<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseDataset<'info>>,
         dataset_id: Pubkey,
     ) -> Result<()> {
         data_marketplace::purchase_dataset(ctx, dataset_id)
     }
     
+    /// Subscribe to a dataset for one subscription period
+    pub fn subscribe_dataset<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseDataset<'info>>,
+        dataset_id: Pubkey,
+    ) -> Result<()> {
+        data_marketplace::subscribe_dataset(ctx, dataset_id)
+    }
+    
+    /// Extend a dataset subscription by one period
+    pub fn renew_subscription<'info>(
+        ctx: Context<'_, '_, '_, 'info, RenewSubscription<'info>>,
+    ) -> Result<()> {
+        data_marketplace::renew_subscription(ctx)
+    }
+    
+    /// Check that a buyer has current access to a dataset, optionally to a specific version
+    pub fn verify_access(
+        ctx: Context<VerifyAccess>,
+    ) -> Result<()> {
+        data_marketplace::verify_access(ctx)
+    }
+    
+    /// Update a dataset's description and preview URI
+    pub fn update_dataset(
+        ctx: Context<UpdateDataset>,
+        description: Option<String>,
+        preview_uri: Option<String>,
+    ) -> Result<()> {
+        data_marketplace::update_dataset(ctx, description, preview_uri)
+    }
+    
+    /// Change a dataset's price
+    pub fn set_dataset_price(
+        ctx: Context<UpdateDataset>,
+        price: u64,
+    ) -> Result<()> {
+        data_marketplace::set_dataset_price(ctx, price)
+    }
+    
+    /// Switch a dataset between one-time and subscription pricing
+    pub fn set_dataset_pricing(
+        ctx: Context<UpdateDataset>,
+        pricing_mode: u8,
+        subscription_period: i64,
+    ) -> Result<()> {
+        data_marketplace::set_dataset_pricing(ctx, pricing_mode, subscription_period)
+    }
+    
+    /// Publish a new version of a dataset; `uri` is public and must point to encrypted data
+    pub fn publish_dataset_version(
+        ctx: Context<PublishDatasetVersion>,
+        content_hash: [u8; 32],
+        byte_size: u64,
+        schema_hash: [u8; 32],
+        uri: String,
+        changelog: String,
+    ) -> Result<()> {
+        data_marketplace::publish_dataset_version(ctx, content_hash, byte_size, schema_hash, uri, changelog)
+    }
+    
+    /// Choose whether new purchases grant one version or all future versions
+    pub fn set_dataset_access_scope(
+        ctx: Context<UpdateDataset>,
+        access_scope: u8,
+    ) -> Result<()> {
+        data_marketplace::set_dataset_access_scope(ctx, access_scope)
+    }
+    
+    /// Divide a dataset's sale proceeds between contributors
+    pub fn set_revenue_split(
+        ctx: Context<SetRevenueSplit>,
+        recipients: Vec<SplitRecipient>,
+    ) -> Result<()> {
+        revenue_split::set_revenue_split(ctx, recipients)
+    }
+    
+    /// Pay a dataset's sale proceeds to its owner again
+    pub fn remove_revenue_split(
+        ctx: Context<RemoveRevenueSplit>,
+    ) -> Result<()> {
+        revenue_split::remove_revenue_split(ctx)
+    }
+    
+    /// Take a dataset off sale
+    pub fn delist_dataset(
+        ctx: Context<UpdateDataset>,
+    ) -> Result<()> {
+        data_marketplace::delist_dataset(ctx)
+    }
+    
+    /// Put a delisted dataset back on sale
+    pub fn relist_dataset(
+        ctx: Context<UpdateDataset>,
+    ) -> Result<()> {
+        data_marketplace::relist_dataset(ctx)
+    }
+    
     /// Rate a dataset after purchase
     pub fn rate_dataset(
         ctx: Context<RateDataset>,
@@ -89,6 +474,117 @@ pub mod datachain_ai {
         data_marketplace::rate_dataset(ctx, dataset_id, rating, review)
     }
     
+    /// Set the x25519 key a dataset decryption key is sealed to
+    pub fn set_encryption_key(
+        ctx: Context<SetEncryptionKey>,
+        encryption_key: [u8; 32],
+    ) -> Result<()> {
+        data_marketplace::set_encryption_key(ctx, encryption_key)
+    }
+    
+    /// Post a dataset decryption key sealed to the buyer's encryption key
+    pub fn deliver_access_key(
+        ctx: Context<DeliverAccessKey>,
+        encrypted_key: Vec<u8>,
+    ) -> Result<()> {
+        data_marketplace::deliver_access_key(ctx, encrypted_key)
+    }
+    
+    /// Let another wallet deliver a dataset's decryption keys
+    pub fn set_delivery_authority(
+        ctx: Context<UpdateDataset>,
+        delivery_authority: Pubkey,
+    ) -> Result<()> {
+        data_marketplace::set_delivery_authority(ctx, delivery_authority)
+    }
+    
+    // Dataset Coupons
+    
+    /// Create a discount coupon for a dataset
+    pub fn create_coupon(
+        ctx: Context<CreateCoupon>,
+        code: String,
+        discount_type: u8,
+        discount_value: u64,
+        max_redemptions: u32,
+        expires_at: Option<i64>,
+        allowed_buyer: Option<Pubkey>,
+    ) -> Result<()> {
+        coupon::create_coupon(ctx, code, discount_type, discount_value, max_redemptions, expires_at, allowed_buyer)
+    }
+    
+    /// Close a coupon and reclaim its rent
+    pub fn close_coupon(
+        ctx: Context<CloseCoupon>,
+    ) -> Result<()> {
+        coupon::close_coupon(ctx)
+    }
+    
+    // Dataset Bundles
+    
+    /// Create a bundle of datasets sold at a single price
+    pub fn create_bundle(
+        ctx: Context<CreateBundle>,
+        name: String,
+        datasets: Vec<Pubkey>,
+        price: u64,
+    ) -> Result<()> {
+        bundle::create_bundle(ctx, name, datasets, price)
+    }
+    
+    /// Purchase access to every dataset in a bundle
+    pub fn purchase_bundle<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseBundle<'info>>,
+    ) -> Result<()> {
+        bundle::purchase_bundle(ctx)
+    }
+    
+    // Purchase Escrow
+    
+    /// Set how long dataset payments are held in escrow
+    pub fn set_escrow_period(
+        ctx: Context<SetEscrowPeriod>,
+        escrow_period: i64,
+    ) -> Result<()> {
+        escrow::set_escrow_period(ctx, escrow_period)
+    }
+    
+    /// Create the escrow vault for a payment mint
+    pub fn initialize_escrow_vault(
+        ctx: Context<InitializeEscrowVault>,
+    ) -> Result<()> {
+        escrow::initialize_escrow_vault(ctx)
+    }
+    
+    /// Confirm delivery and release the escrowed payment
+    pub fn confirm_delivery<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleEscrow<'info>>,
+    ) -> Result<()> {
+        escrow::confirm_delivery(ctx)
+    }
+    
+    /// Dispute a purchase while its payment is in escrow
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+    ) -> Result<()> {
+        escrow::open_dispute(ctx)
+    }
+    
+    /// Release an undisputed payment after the escrow window
+    pub fn release_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleEscrow<'info>>,
+    ) -> Result<()> {
+        escrow::release_escrow(ctx)
+    }
+    
+    /// Settle a dispute, optionally refunding the buyer
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        refund_buyer: bool,
+    ) -> Result<()> {
+        escrow::resolve_dispute(ctx, refund_buyer)
+    }
+    
     // AI Model Marketplace
     
     /// Register a new AI model
@@ -124,6 +620,17 @@ pub mod datachain_ai {
     
     // Governance
     
+    /// Create the governance config for the DATA token
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        voting_period: i64,
+        execution_delay: i64,
+        quorum_percentage: u8,
+        approval_threshold_percentage: u8,
+    ) -> Result<()> {
+        governance::initialize_governance(ctx, voting_period, execution_delay, quorum_percentage, approval_threshold_percentage)
+    }
+    
     /// Create a governance proposal
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
@@ -145,61 +652,61 @@ pub mod datachain_ai {
         governance::vote(ctx, proposal_id, vote)
     }
     
-    /// Execute an approved proposal
+    /// Execute an approved proposal, applying its emission policy when it carries one
     pub fn execute_proposal(
         ctx: Context<ExecuteProposal>,
         proposal_id: Pubkey,
     ) -> Result<()> {
         governance::execute_proposal(ctx, proposal_id)
     }
-}
-
-// Context structs for instruction validation
-
-#[derive(Accounts)]
-pub struct InitializeToken<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 9,
-        mint::authority = authority.key(),
-    )]
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        mut,
-        constraint = mint.mint_authority.unwrap() == authority.key()
-    )]
-    pub mint: Account<'info, Mint>,
-    #[account(
-        mut,
-        constraint = recipient.mint == mint.key()
-    )]
-    pub recipient: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    pub sender: Signer<'info>,
-    #[account(
-        mut,
-        constraint = sender_token.owner == sender.key()
-    )]
-    pub sender_token: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub recipient_token: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    
+    // Staking
+    
+    /// Initialize a DATA stake pool with its stake and reward vaults
+    pub fn initialize_stake_pool(
+        ctx: Context<InitializeStakePool>,
+    ) -> Result<()> {
+        staking::initialize_stake_pool(ctx)
+    }
+    
+    /// Stake DATA tokens for one of the lockup tiers
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+        lockup_tier: u8,
+    ) -> Result<()> {
+        staking::stake(ctx, amount, lockup_tier)
+    }
+    
+    /// Unstake an unlocked position, paying out pending rewards
+    pub fn unstake(
+        ctx: Context<Unstake>,
+    ) -> Result<()> {
+        staking::unstake(ctx)
+    }
+    
+    /// Claim accrued fee-share rewards for a stake position
+    pub fn claim_rewards(
+        ctx: Context<ClaimRewards>,
+    ) -> Result<()> {
+        staking::claim_rewards(ctx)
+    }
+    
+    /// Route a share of data marketplace fees to a stake pool
+    pub fn set_staking_fee_share(
+        ctx: Context<SetStakingFeeShare>,
+        staking_fee_percentage: u8,
+    ) -> Result<()> {
+        data_marketplace::set_staking_fee_share(ctx, staking_fee_percentage)
+    }
+    
+    /// Route a share of AI model marketplace fees to a stake pool
+    pub fn set_model_staking_fee_share(
+        ctx: Context<SetModelStakingFeeShare>,
+        staking_fee_percentage: u8,
+    ) -> Result<()> {
+        ai_models::set_model_staking_fee_share(ctx, staking_fee_percentage)
+    }
 }
 
 // Other account validation structs would be defined here
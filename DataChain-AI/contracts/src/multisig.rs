@@ -0,0 +1,345 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_MINTING};
+use crate::data_token::{self, TokenInfo, MINT_AUTHORITY_SEED};
+use crate::events::*;
+
+// Constants for the mint multisig
+pub const MULTISIG_VERSION: u8 = 1;
+pub const MINT_MULTISIG_SEED: &[u8] = b"mint_multisig";
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+// M-of-N multisig that mints in place of the token authority
+#[account]
+pub struct MintMultisig {
+    // Token info governed by this multisig
+    pub token_info: Pubkey,
+    
+    // Signer set and approval threshold
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    
+    // Statistics
+    pub proposal_count: u64,
+    
+    // Bump of the multisig PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl MintMultisig {
+    // Index of a key in the signer set
+    pub fn signer_index(&self, key: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|signer| signer == key)
+            .ok_or(error!(DataChainError::NotMultisigSigner))
+    }
+}
+
+// Mint proposal awaiting multisig approval
+#[account]
+pub struct MintProposal {
+    // Multisig the proposal belongs to
+    pub multisig: Pubkey,
+    
+    // Proposer
+    pub proposer: Pubkey,
+    
+    // Mint details
+    pub recipient: Pubkey,
+    pub amount: u64,
+    
+    // Approvals, one bit per signer index
+    pub approvals: u16,
+    pub approval_count: u8,
+    
+    // Execution status
+    pub executed: bool,
+    
+    // Time information
+    pub created_at: i64,
+    pub executed_at: Option<i64>,
+}
+
+// Context for putting minting behind a multisig
+#[derive(Accounts)]
+pub struct CreateMintMultisig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<MintMultisig>() + (32 * MAX_MULTISIG_SIGNERS),
+        seeds = [MINT_MULTISIG_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, MintMultisig>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Context for proposing a mint
+#[derive(Accounts)]
+pub struct ProposeMint<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    #[account(mut)]
+    pub multisig: Account<'info, MintMultisig>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + std::mem::size_of::<MintProposal>(),
+    )]
+    pub proposal: Account<'info, MintProposal>,
+    
+    #[account(
+        constraint = token_info.key() == multisig.token_info @ DataChainError::InvalidParameters
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        constraint = recipient.mint == token_info.mint @ DataChainError::InvalidParameters
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for approving a mint proposal
+#[derive(Accounts)]
+pub struct ApproveMint<'info> {
+    pub signer: Signer<'info>,
+    
+    pub multisig: Account<'info, MintMultisig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key() @ DataChainError::InvalidParameters,
+        constraint = !proposal.executed @ DataChainError::MintProposalExecuted
+    )]
+    pub proposal: Account<'info, MintProposal>,
+}
+
+// Context for executing an approved mint proposal
+#[derive(Accounts)]
+pub struct ExecuteMint<'info> {
+    pub executor: Signer<'info>,
+    
+    #[account(
+        seeds = [MINT_MULTISIG_SEED, token_info.key().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, MintMultisig>,
+    
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key() @ DataChainError::InvalidParameters,
+        constraint = !proposal.executed @ DataChainError::MintProposalExecuted,
+        constraint = proposal.approval_count >= multisig.threshold @ DataChainError::MintThresholdNotReached
+    )]
+    pub proposal: Account<'info, MintProposal>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.mint_multisig == Some(multisig.key()) @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: Program mint authority PDA
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = recipient.key() == proposal.recipient @ DataChainError::InvalidParameters
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_MINTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of multisig functions
+pub fn create_mint_multisig(
+    ctx: Context<CreateMintMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    // Validate signer set
+    if signers.is_empty() || signers.len() > MAX_MULTISIG_SIGNERS {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    if threshold == 0 || threshold as usize > signers.len() {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    for (i, signer) in signers.iter().enumerate() {
+        if signers[..i].contains(signer) {
+            return err!(DataChainError::InvalidParameters);
+        }
+    }
+    
+    let token_info = &mut ctx.accounts.token_info;
+    let multisig = &mut ctx.accounts.multisig;
+    
+    // Initialize multisig
+    multisig.token_info = token_info.key();
+    multisig.signers = signers;
+    multisig.threshold = threshold;
+    multisig.proposal_count = 0;
+    multisig.bump = *ctx.bumps.get("multisig").ok_or(DataChainError::SystemError)?;
+    multisig.version = MULTISIG_VERSION;
+    
+    // Minting now requires multisig approval; the authority keeps its other roles
+    token_info.mint_multisig = Some(multisig.key());
+    
+    emit!(MintMultisigCreated {
+        token_info: token_info.key(),
+        multisig: multisig.key(),
+        threshold,
+        signer_count: multisig.signers.len() as u8,
+    });
+    
+    msg!("Mint multisig created with {}-of-{} signers", threshold, multisig.signers.len());
+    
+    Ok(())
+}
+
+pub fn propose_mint(
+    ctx: Context<ProposeMint>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let multisig = &mut ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+    let proposer = ctx.accounts.proposer.key();
+    let clock = &ctx.accounts.clock;
+    
+    let signer_index = multisig.signer_index(&proposer)?;
+    
+    // Initialize proposal, counting the proposer's approval
+    proposal.multisig = multisig.key();
+    proposal.proposer = proposer;
+    proposal.recipient = ctx.accounts.recipient.key();
+    proposal.amount = amount;
+    proposal.approvals = 1 << signer_index;
+    proposal.approval_count = 1;
+    proposal.executed = false;
+    proposal.created_at = clock.unix_timestamp;
+    proposal.executed_at = None;
+    
+    multisig.proposal_count = multisig.proposal_count.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(MintProposed {
+        multisig: multisig.key(),
+        proposal: proposal.key(),
+        proposer,
+        recipient: proposal.recipient,
+        amount,
+    });
+    
+    msg!("Mint of {} DATA tokens proposed by {}", amount, proposer);
+    
+    Ok(())
+}
+
+pub fn approve_mint(
+    ctx: Context<ApproveMint>,
+) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+    let signer = ctx.accounts.signer.key();
+    
+    let signer_bit = 1u16 << multisig.signer_index(&signer)?;
+    
+    if proposal.approvals & signer_bit != 0 {
+        return err!(DataChainError::MintProposalAlreadyApproved);
+    }
+    
+    proposal.approvals |= signer_bit;
+    proposal.approval_count = proposal.approval_count.checked_add(1).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(MintApproved {
+        multisig: multisig.key(),
+        proposal: proposal.key(),
+        signer,
+        approval_count: proposal.approval_count,
+    });
+    
+    msg!("Mint proposal approved by {} ({}/{})", signer, proposal.approval_count, multisig.threshold);
+    
+    Ok(())
+}
+
+pub fn execute_mint(
+    ctx: Context<ExecuteMint>,
+) -> Result<()> {
+    let token_info = &mut ctx.accounts.token_info;
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = &ctx.accounts.clock;
+    
+    // Same emission and supply rules as single-signer minting
+    token_info.check_mint_allowed(proposal.amount, clock.unix_timestamp)?;
+    
+    data_token::mint_with_program_authority(
+        token_info,
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.mint_authority,
+        ctx.accounts.token_program.to_account_info(),
+        proposal.amount,
+    )?;
+    
+    token_info.record_mint(proposal.amount, clock.unix_timestamp)?;
+    
+    proposal.executed = true;
+    proposal.executed_at = Some(clock.unix_timestamp);
+    
+    emit!(MintProposalExecuted {
+        multisig: ctx.accounts.multisig.key(),
+        proposal: proposal.key(),
+        recipient: proposal.recipient,
+        amount: proposal.amount,
+        current_supply: token_info.current_supply,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Multisig minted {} DATA tokens to {}", proposal.amount, proposal.recipient);
+    
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use crate::errors::DataChainError;
+use crate::data_token::TokenInfo;
+use crate::program::DatachainAi;
+use crate::events::*;
+
+// Constants for protocol-wide emergency controls
+pub const PROTOCOL_VERSION: u8 = 1;
+pub const PROTOCOL_STATE_SEED: &[u8] = b"protocol_state";
+
+// Per-subsystem pause bits
+pub const PAUSE_MINTING: u8 = 1 << 0;
+pub const PAUSE_TRANSFERS: u8 = 1 << 1;
+pub const PAUSE_DATASET_PURCHASES: u8 = 1 << 2;
+pub const PAUSE_MODEL_USAGE: u8 = 1 << 3;
+pub const PAUSE_VOTING: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 = PAUSE_MINTING | PAUSE_TRANSFERS | PAUSE_DATASET_PURCHASES | PAUSE_MODEL_USAGE | PAUSE_VOTING;
+
+// Protocol state structure
+#[account]
+pub struct ProtocolState {
+    // Authority that can unpause and rotate the guardian
+    pub authority: Pubkey,
+    
+    // Authority proposed to take over, until it accepts
+    pub pending_authority: Option<Pubkey>,
+    
+    // Guardian that can pause instantly
+    pub guardian: Pubkey,
+    
+    // Canonical DATA token info the rest of the protocol is anchored to
+    pub token_info: Pubkey,
+    
+    // Paused subsystems
+    pub paused: u8,
+    
+    // Time information
+    pub last_changed_at: i64,
+    
+    // Bump of the protocol state PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl ProtocolState {
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+}
+
+// Context for initializing the protocol state, restricted to the program upgrade authority
+#[derive(Accounts)]
+pub struct InitializeProtocolState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Guardian wallet, only stored on the protocol state
+    pub guardian: AccountInfo<'info>,
+    
+    #[account(
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ DataChainError::InvalidParameters
+    )]
+    pub program: Program<'info, DatachainAi>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ DataChainError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<ProtocolState>(),
+        seeds = [PROTOCOL_STATE_SEED],
+        bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for pausing subsystems
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub signer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.guardian == signer.key() || protocol_state.authority == signer.key() @ DataChainError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for unpausing subsystems and rotating the guardian or authority
+#[derive(Accounts)]
+pub struct ProtocolAdmin<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.authority == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for accepting the protocol authority
+#[derive(Accounts)]
+pub struct AcceptProtocolAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.pending_authority == Some(new_authority.key()) @ DataChainError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of protocol functions
+pub fn initialize_protocol_state(
+    ctx: Context<InitializeProtocolState>,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    
+    protocol_state.authority = ctx.accounts.authority.key();
+    protocol_state.pending_authority = None;
+    protocol_state.guardian = ctx.accounts.guardian.key();
+    protocol_state.token_info = ctx.accounts.token_info.key();
+    protocol_state.paused = 0;
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    protocol_state.bump = *ctx.bumps.get("protocol_state").ok_or(DataChainError::SystemError)?;
+    protocol_state.version = PROTOCOL_VERSION;
+    
+    emit!(ProtocolStateInitialized {
+        authority: protocol_state.authority,
+        guardian: protocol_state.guardian,
+        token_info: protocol_state.token_info,
+    });
+    
+    msg!("Protocol state initialized with guardian {}", protocol_state.guardian);
+    
+    Ok(())
+}
+
+pub fn pause(
+    ctx: Context<Pause>,
+    flags: u8,
+) -> Result<()> {
+    if flags == 0 || flags & !PAUSE_ALL != 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    
+    protocol_state.paused |= flags;
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(SubsystemsPaused {
+        signer: ctx.accounts.signer.key(),
+        flags,
+        paused: protocol_state.paused,
+        timestamp: protocol_state.last_changed_at,
+    });
+    
+    msg!("Protocol paused by {}, flags now {:#07b}", ctx.accounts.signer.key(), protocol_state.paused);
+    
+    Ok(())
+}
+
+pub fn unpause(
+    ctx: Context<ProtocolAdmin>,
+    flags: u8,
+) -> Result<()> {
+    if flags == 0 || flags & !PAUSE_ALL != 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    
+    protocol_state.paused &= !flags;
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(SubsystemsUnpaused {
+        authority: ctx.accounts.authority.key(),
+        flags,
+        paused: protocol_state.paused,
+        timestamp: protocol_state.last_changed_at,
+    });
+    
+    msg!("Protocol unpaused by {}, flags now {:#07b}", ctx.accounts.authority.key(), protocol_state.paused);
+    
+    Ok(())
+}
+
+pub fn set_guardian(
+    ctx: Context<ProtocolAdmin>,
+    new_guardian: Pubkey,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    
+    protocol_state.guardian = new_guardian;
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(GuardianSet {
+        guardian: new_guardian,
+    });
+    
+    msg!("Protocol guardian set to {}", new_guardian);
+    
+    Ok(())
+}
+
+pub fn request_protocol_authority_transfer(
+    ctx: Context<ProtocolAdmin>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    
+    protocol_state.pending_authority = Some(new_authority);
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(ProtocolAuthorityTransferRequested {
+        authority: protocol_state.authority,
+        new_authority,
+    });
+    
+    msg!("Protocol authority transfer requested to {}", new_authority);
+    
+    Ok(())
+}
+
+pub fn accept_protocol_authority(
+    ctx: Context<AcceptProtocolAuthority>,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let new_authority = ctx.accounts.new_authority.key();
+    
+    protocol_state.authority = new_authority;
+    protocol_state.pending_authority = None;
+    protocol_state.last_changed_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(ProtocolAuthorityTransferred {
+        authority: new_authority,
+    });
+    
+    msg!("Protocol authority accepted by {}", new_authority);
+    
+    Ok(())
+}
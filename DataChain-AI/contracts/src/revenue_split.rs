@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::data_marketplace::{self, Dataset};
+use crate::events::*;
+
+// Constants for dataset revenue splits
+pub const REVENUE_SPLIT_VERSION: u8 = 1;
+pub const REVENUE_SPLIT_SEED: &[u8] = b"revenue_split";
+pub const MAX_SPLIT_RECIPIENTS: usize = 10;
+pub const TOTAL_SHARE_BPS: u16 = 10_000;
+pub const REVENUE_SPLIT_SPACE: usize = 8 + std::mem::size_of::<RevenueSplit>() + (MAX_SPLIT_RECIPIENTS * std::mem::size_of::<SplitRecipient>());
+
+// Recipient of a share of a dataset's sale proceeds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SplitRecipient {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+// Division of a dataset's sale proceeds between contributors
+#[account]
+pub struct RevenueSplit {
+    // The dataset whose proceeds are split
+    pub dataset: Pubkey,
+    
+    // Recipients, in the order their token accounts are passed; shares sum to 10000 basis points
+    pub recipients: Vec<SplitRecipient>,
+    
+    // Time information
+    pub updated_at: i64,
+    
+    // Bump of the revenue split PDA
+    pub bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl RevenueSplit {
+    // Each recipient's share of an amount, rounded down, with the rounding dust paid to the first recipient
+    pub fn shares(&self, amount: u64) -> Result<Vec<u64>> {
+        let mut shares = Vec::with_capacity(self.recipients.len());
+        
+        for recipient in &self.recipients {
+            let share = (amount as u128)
+                .checked_mul(recipient.share_bps as u128)
+                .ok_or(DataChainError::InvalidParameters)?
+                .checked_div(TOTAL_SHARE_BPS as u128)
+                .ok_or(DataChainError::InvalidParameters)? as u64;
+            shares.push(share);
+        }
+        
+        let distributed = shares.iter().try_fold(0u64, |total, share| total.checked_add(*share))
+            .ok_or(DataChainError::InvalidParameters)?;
+        let dust = amount.checked_sub(distributed).ok_or(DataChainError::InvalidParameters)?;
+        
+        let first_share = shares.first_mut().ok_or(DataChainError::InvalidRevenueSplit)?;
+        *first_share = first_share.checked_add(dust).ok_or(DataChainError::InvalidParameters)?;
+        
+        Ok(shares)
+    }
+}
+
+// Recipients must be distinct, each with a non-zero share, and the shares must sum to 10000 basis points
+pub fn validate_recipients(recipients: &[SplitRecipient]) -> Result<()> {
+    if recipients.is_empty() || recipients.len() > MAX_SPLIT_RECIPIENTS {
+        return err!(DataChainError::InvalidRevenueSplit);
+    }
+    
+    let mut total_bps: u16 = 0;
+    for (index, recipient) in recipients.iter().enumerate() {
+        if recipient.share_bps == 0 || recipients[..index].iter().any(|other| other.wallet == recipient.wallet) {
+            return err!(DataChainError::InvalidRevenueSplit);
+        }
+        
+        total_bps = total_bps.checked_add(recipient.share_bps).ok_or(DataChainError::InvalidRevenueSplit)?;
+    }
+    
+    if total_bps != TOTAL_SHARE_BPS {
+        return err!(DataChainError::InvalidRevenueSplit);
+    }
+    
+    Ok(())
+}
+
+// Address of a dataset's revenue split
+pub fn find_revenue_split_address(dataset: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REVENUE_SPLIT_SEED, dataset.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Context for the owner setting a dataset's revenue split
+#[derive(Accounts)]
+pub struct SetRevenueSplit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = dataset.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    /// CHECK: Revenue split PDA, created on first use and overwritten afterwards
+    #[account(
+        mut,
+        seeds = [REVENUE_SPLIT_SEED, dataset.key().as_ref()],
+        bump
+    )]
+    pub revenue_split: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for the owner removing a dataset's revenue split
+#[derive(Accounts)]
+pub struct RemoveRevenueSplit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = dataset.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub dataset: Account<'info, Dataset>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [REVENUE_SPLIT_SEED, dataset.key().as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+    
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of revenue split functions
+pub fn set_revenue_split(
+    ctx: Context<SetRevenueSplit>,
+    recipients: Vec<SplitRecipient>,
+) -> Result<()> {
+    validate_recipients(&recipients)?;
+    
+    let dataset = &mut ctx.accounts.dataset;
+    let revenue_split_info = &ctx.accounts.revenue_split;
+    let clock = &ctx.accounts.clock;
+    let dataset_key = dataset.key();
+    let revenue_split_bump = *ctx.bumps.get("revenue_split").ok_or(DataChainError::SystemError)?;
+    
+    if revenue_split_info.data_is_empty() {
+        let seeds = &[
+            REVENUE_SPLIT_SEED,
+            dataset_key.as_ref(),
+            &[revenue_split_bump],
+        ];
+        
+        data_marketplace::create_pda_account(
+            &ctx.accounts.owner.to_account_info(),
+            revenue_split_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.rent,
+            REVENUE_SPLIT_SPACE,
+            &seeds[..],
+        )?;
+    }
+    
+    let revenue_split = RevenueSplit {
+        dataset: dataset_key,
+        recipients,
+        updated_at: clock.unix_timestamp,
+        bump: revenue_split_bump,
+        version: REVENUE_SPLIT_VERSION,
+    };
+    
+    data_marketplace::write_account(revenue_split_info, &revenue_split)?;
+    
+    dataset.has_revenue_split = true;
+    dataset.updated_at = clock.unix_timestamp;
+    
+    emit!(RevenueSplitSet {
+        dataset: dataset_key,
+        revenue_split: revenue_split_info.key(),
+        recipient_count: revenue_split.recipients.len() as u8,
+    });
+    
+    msg!("Revenue split set for {} across {} recipients", dataset.name, revenue_split.recipients.len());
+    
+    Ok(())
+}
+
+pub fn remove_revenue_split(
+    ctx: Context<RemoveRevenueSplit>,
+) -> Result<()> {
+    let dataset = &mut ctx.accounts.dataset;
+    
+    dataset.has_revenue_split = false;
+    dataset.updated_at = ctx.accounts.clock.unix_timestamp;
+    
+    emit!(RevenueSplitRemoved {
+        dataset: dataset.key(),
+        revenue_split: ctx.accounts.revenue_split.key(),
+    });
+    
+    msg!("Revenue split removed from {}", dataset.name);
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn recipients(shares_bps: &[u16]) -> Vec<SplitRecipient> {
+        shares_bps.iter().map(|share_bps| SplitRecipient {
+            wallet: Pubkey::new_unique(),
+            share_bps: *share_bps,
+        }).collect()
+    }
+    
+    fn revenue_split(shares_bps: &[u16]) -> RevenueSplit {
+        RevenueSplit {
+            dataset: Pubkey::default(),
+            recipients: recipients(shares_bps),
+            updated_at: 0,
+            bump: 255,
+            version: REVENUE_SPLIT_VERSION,
+        }
+    }
+    
+    #[test]
+    fn shares_follow_basis_points() {
+        let split = revenue_split(&[5_000, 3_000, 2_000]);
+        
+        assert_eq!(split.shares(1_000).unwrap(), vec![500, 300, 200]);
+        assert_eq!(split.shares(0).unwrap(), vec![0, 0, 0]);
+        
+        // Shares are computed in u128, so the full amount range is split without overflow
+        let shares = revenue_split(&[5_000, 5_000]).shares(u64::MAX).unwrap();
+        assert_eq!(shares, vec![u64::MAX / 2 + 1, u64::MAX / 2]);
+    }
+    
+    #[test]
+    fn dust_goes_to_the_first_listed_recipient_whatever_its_share() {
+        // A one basis point recipient listed first collects the dust of the large share
+        assert_eq!(revenue_split(&[1, 9_999]).shares(9_999).unwrap(), vec![1, 9_998]);
+        
+        // Listed last, the dust goes to the large share instead
+        assert_eq!(revenue_split(&[9_999, 1]).shares(9_999).unwrap(), vec![9_999, 0]);
+    }
+    
+    #[test]
+    fn an_empty_split_has_no_shares() {
+        assert!(revenue_split(&[]).shares(100).is_err());
+    }
+    
+    #[test]
+    fn invalid_recipients_are_rejected() {
+        assert!(validate_recipients(&recipients(&[6_000, 4_000])).is_ok());
+        
+        assert!(validate_recipients(&[]).is_err());
+        assert!(validate_recipients(&recipients(&[6_000, 3_999])).is_err());
+        assert!(validate_recipients(&recipients(&[10_000, 0])).is_err());
+        
+        // Shares whose total overflows u16 are rejected rather than wrapping to 10000
+        assert!(validate_recipients(&recipients(&[u16::MAX, 10_001])).is_err());
+        
+        // The same wallet cannot be listed twice
+        let mut duplicated = recipients(&[5_000, 5_000]);
+        duplicated[1].wallet = duplicated[0].wallet;
+        assert!(validate_recipients(&duplicated).is_err());
+    }
+    
+    #[test]
+    fn recipient_limit_is_inclusive() {
+        assert!(validate_recipients(&recipients(&[1_000; MAX_SPLIT_RECIPIENTS])).is_ok());
+        
+        let mut too_many = recipients(&[1_000; MAX_SPLIT_RECIPIENTS]);
+        too_many[0].share_bps = 999;
+        too_many.extend(recipients(&[1]));
+        assert!(validate_recipients(&too_many).is_err());
+    }
+}
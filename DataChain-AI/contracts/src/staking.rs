@@ -0,0 +1,609 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::events::*;
+
+// Constants for DATA staking
+pub const STAKING_VERSION: u8 = 1;
+pub const STAKE_POOL_AUTHORITY_SEED: &[u8] = b"stake_pool_authority";
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+pub const MIN_STAKE_AMOUNT: u64 = 100_000_000_000; // 100 DATA with 9 decimals
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000; // Scaling factor for reward per share
+
+// Lockup tiers: minimum lockup in seconds and reward weight (percentage of staked amount)
+pub const LOCKUP_TIER_DURATIONS: [i64; 3] = [
+    30 * 24 * 60 * 60,  // 30 days
+    90 * 24 * 60 * 60,  // 90 days
+    180 * 24 * 60 * 60, // 180 days
+];
+pub const LOCKUP_TIER_WEIGHTS: [u64; 3] = [100, 200, 300]; // Fee-share weights, longer lockups earn a larger share of routed fees
+
+// Stake pool structure
+#[account]
+pub struct StakePool {
+    // Authority that created the pool
+    pub authority: Pubkey,
+    
+    // Staked token mint and the pool vaults
+    pub stake_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    
+    // Staking totals
+    pub total_staked: u64,
+    pub total_weighted_stake: u64,
+    
+    // Reward accounting
+    pub acc_reward_per_share: u128,
+    pub last_reward_balance: u64,
+    pub total_rewards_distributed: u64,
+    
+    // Bump of the pool authority PDA
+    pub authority_bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl StakePool {
+    // Fold any fees that landed in the reward vault since the last update into the accumulator
+    pub fn update_rewards(&mut self, reward_vault_balance: u64) -> Result<()> {
+        // Rewards arriving while nothing is staked wait for the first staker
+        if self.total_weighted_stake == 0 {
+            return Ok(());
+        }
+        
+        let new_rewards = reward_vault_balance.checked_sub(self.last_reward_balance).ok_or(DataChainError::InvalidParameters)?;
+        if new_rewards == 0 {
+            return Ok(());
+        }
+        
+        let reward_per_share = (new_rewards as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(DataChainError::InvalidParameters)?
+            .checked_div(self.total_weighted_stake as u128)
+            .ok_or(DataChainError::InvalidParameters)?;
+        
+        self.acc_reward_per_share = self.acc_reward_per_share.checked_add(reward_per_share).ok_or(DataChainError::InvalidParameters)?;
+        self.last_reward_balance = reward_vault_balance;
+        
+        Ok(())
+    }
+    
+    // Rewards accrued to a weighted stake at the current accumulator value
+    pub fn accrued_rewards(&self, weighted_amount: u64) -> Result<u128> {
+        let accrued = (weighted_amount as u128)
+            .checked_mul(self.acc_reward_per_share)
+            .ok_or(DataChainError::InvalidParameters)?
+            .checked_div(REWARD_PRECISION)
+            .ok_or(DataChainError::InvalidParameters)?;
+        
+        Ok(accrued)
+    }
+}
+
+// Stake position structure
+#[account]
+pub struct StakePosition {
+    // Position owner
+    pub owner: Pubkey,
+    
+    // Pool the position belongs to
+    pub pool: Pubkey,
+    
+    // Stake details
+    pub amount: u64,
+    pub weighted_amount: u64,
+    pub lockup_tier: u8,
+    
+    // Time information
+    pub staked_at: i64,
+    pub unlock_at: i64,
+    
+    // Rewards already accounted for this position
+    pub reward_debt: u128,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+// Context for initializing a stake pool
+#[derive(Accounts)]
+pub struct InitializeStakePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub stake_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<StakePool>(),
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// CHECK: PDA signing for the pool vaults
+    #[account(
+        seeds = [STAKE_POOL_AUTHORITY_SEED, stake_pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [STAKE_VAULT_SEED, stake_pool.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = pool_authority,
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [REWARD_VAULT_SEED, stake_pool.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = pool_authority,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Context for staking tokens
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<StakePosition>(),
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(
+        mut,
+        constraint = stake_vault.key() == stake_pool.stake_vault @ DataChainError::InvalidParameters
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        constraint = reward_vault.key() == stake_pool.reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_token.mint == stake_pool.stake_mint @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for unstaking tokens
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    #[account(
+        mut,
+        close = owner,
+        constraint = stake_position.owner == owner.key() @ DataChainError::Unauthorized,
+        constraint = stake_position.pool == stake_pool.key() @ DataChainError::InvalidParameters,
+        constraint = stake_position.unlock_at <= clock.unix_timestamp @ DataChainError::StakeLocked
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    /// CHECK: PDA signing for the pool vaults
+    #[account(
+        seeds = [STAKE_POOL_AUTHORITY_SEED, stake_pool.key().as_ref()],
+        bump = stake_pool.authority_bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = stake_vault.key() == stake_pool.stake_vault @ DataChainError::InvalidParameters
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault.key() == stake_pool.reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_token.mint == stake_pool.stake_mint @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for claiming staking rewards
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    #[account(
+        mut,
+        constraint = stake_position.owner == owner.key() @ DataChainError::Unauthorized,
+        constraint = stake_position.pool == stake_pool.key() @ DataChainError::InvalidParameters
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    /// CHECK: PDA signing for the pool vaults
+    #[account(
+        seeds = [STAKE_POOL_AUTHORITY_SEED, stake_pool.key().as_ref()],
+        bump = stake_pool.authority_bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault.key() == stake_pool.reward_vault @ DataChainError::InvalidParameters
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_token.mint == stake_pool.stake_mint @ DataChainError::InvalidParameters,
+        constraint = owner_token.owner == owner.key() @ DataChainError::Unauthorized
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+// Implementation of staking functions
+pub fn initialize_stake_pool(
+    ctx: Context<InitializeStakePool>,
+) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    
+    stake_pool.authority = ctx.accounts.authority.key();
+    stake_pool.stake_mint = ctx.accounts.stake_mint.key();
+    stake_pool.stake_vault = ctx.accounts.stake_vault.key();
+    stake_pool.reward_vault = ctx.accounts.reward_vault.key();
+    stake_pool.total_staked = 0;
+    stake_pool.total_weighted_stake = 0;
+    stake_pool.acc_reward_per_share = 0;
+    stake_pool.last_reward_balance = 0;
+    stake_pool.total_rewards_distributed = 0;
+    stake_pool.authority_bump = *ctx.bumps.get("pool_authority").ok_or(DataChainError::SystemError)?;
+    stake_pool.version = STAKING_VERSION;
+    
+    emit!(StakePoolInitialized {
+        stake_pool: stake_pool.key(),
+        stake_mint: stake_pool.stake_mint,
+    });
+    
+    msg!("Stake pool initialized for mint {}", stake_pool.stake_mint);
+    
+    Ok(())
+}
+
+pub fn stake(
+    ctx: Context<Stake>,
+    amount: u64,
+    lockup_tier: u8,
+) -> Result<()> {
+    if amount < MIN_STAKE_AMOUNT {
+        return err!(DataChainError::StakeBelowMinimum);
+    }
+    
+    let tier = lockup_tier as usize;
+    if tier >= LOCKUP_TIER_DURATIONS.len() {
+        return err!(DataChainError::InvalidLockupTier);
+    }
+    
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let clock = &ctx.accounts.clock;
+    
+    // Bring the accumulator up to date before the stake weight changes
+    stake_pool.update_rewards(ctx.accounts.reward_vault.amount)?;
+    
+    let weighted_amount = (amount as u128)
+        .checked_mul(LOCKUP_TIER_WEIGHTS[tier] as u128)
+        .ok_or(DataChainError::InvalidParameters)?
+        .checked_div(100)
+        .ok_or(DataChainError::InvalidParameters)? as u64;
+    
+    // Transfer stake into the pool vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner_token.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        ),
+        amount,
+    )?;
+    
+    // Initialize stake position
+    stake_position.owner = ctx.accounts.owner.key();
+    stake_position.pool = stake_pool.key();
+    stake_position.amount = amount;
+    stake_position.weighted_amount = weighted_amount;
+    stake_position.lockup_tier = lockup_tier;
+    stake_position.staked_at = clock.unix_timestamp;
+    stake_position.unlock_at = clock.unix_timestamp.checked_add(LOCKUP_TIER_DURATIONS[tier]).ok_or(DataChainError::InvalidTimestamp)?;
+    stake_position.reward_debt = stake_pool.accrued_rewards(weighted_amount)?;
+    stake_position.version = STAKING_VERSION;
+    
+    // Update pool totals
+    stake_pool.total_staked = stake_pool.total_staked.checked_add(amount).ok_or(DataChainError::InvalidParameters)?;
+    stake_pool.total_weighted_stake = stake_pool.total_weighted_stake.checked_add(weighted_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(Staked {
+        stake_pool: stake_pool.key(),
+        stake_position: stake_position.key(),
+        owner: stake_position.owner,
+        amount,
+        weighted_amount,
+        lockup_tier,
+        unlock_at: stake_position.unlock_at,
+    });
+    
+    msg!("Staked {} DATA tokens until {}", amount, stake_position.unlock_at);
+    
+    Ok(())
+}
+
+pub fn unstake(
+    ctx: Context<Unstake>,
+) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let stake_position = &ctx.accounts.stake_position;
+    
+    stake_pool.update_rewards(ctx.accounts.reward_vault.amount)?;
+    
+    let pending_rewards = stake_pool.accrued_rewards(stake_position.weighted_amount)?
+        .checked_sub(stake_position.reward_debt)
+        .ok_or(DataChainError::InvalidParameters)? as u64;
+    
+    let pool_key = stake_pool.key();
+    let seeds = &[
+        STAKE_POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        &[stake_pool.authority_bump],
+    ];
+    
+    // Pay out any outstanding rewards
+    if pending_rewards > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.owner_token.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        };
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            pending_rewards,
+        )?;
+        
+        stake_pool.last_reward_balance = stake_pool.last_reward_balance.checked_sub(pending_rewards).ok_or(DataChainError::InvalidParameters)?;
+        stake_pool.total_rewards_distributed = stake_pool.total_rewards_distributed.checked_add(pending_rewards).ok_or(DataChainError::InvalidParameters)?;
+    }
+    
+    // Return the staked tokens
+    {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            to: ctx.accounts.owner_token.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        };
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            stake_position.amount,
+        )?;
+    }
+    
+    // Update pool totals
+    stake_pool.total_staked = stake_pool.total_staked.checked_sub(stake_position.amount).ok_or(DataChainError::InvalidParameters)?;
+    stake_pool.total_weighted_stake = stake_pool.total_weighted_stake.checked_sub(stake_position.weighted_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(Unstaked {
+        stake_pool: stake_pool.key(),
+        stake_position: stake_position.key(),
+        owner: stake_position.owner,
+        amount: stake_position.amount,
+        rewards: pending_rewards,
+    });
+    
+    msg!("Unstaked {} DATA tokens with {} rewards", stake_position.amount, pending_rewards);
+    
+    Ok(())
+}
+
+pub fn claim_rewards(
+    ctx: Context<ClaimRewards>,
+) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let stake_position = &mut ctx.accounts.stake_position;
+    
+    stake_pool.update_rewards(ctx.accounts.reward_vault.amount)?;
+    
+    let accrued_rewards = stake_pool.accrued_rewards(stake_position.weighted_amount)?;
+    let pending_rewards = accrued_rewards
+        .checked_sub(stake_position.reward_debt)
+        .ok_or(DataChainError::InvalidParameters)? as u64;
+    
+    if pending_rewards == 0 {
+        return err!(DataChainError::NothingToClaim);
+    }
+    
+    let pool_key = stake_pool.key();
+    let seeds = &[
+        STAKE_POOL_AUTHORITY_SEED,
+        pool_key.as_ref(),
+        &[stake_pool.authority_bump],
+    ];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.owner_token.to_account_info(),
+        authority: ctx.accounts.pool_authority.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+        pending_rewards,
+    )?;
+    
+    // Update reward accounting
+    stake_position.reward_debt = accrued_rewards;
+    stake_pool.last_reward_balance = stake_pool.last_reward_balance.checked_sub(pending_rewards).ok_or(DataChainError::InvalidParameters)?;
+    stake_pool.total_rewards_distributed = stake_pool.total_rewards_distributed.checked_add(pending_rewards).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(StakingRewardsClaimed {
+        stake_pool: stake_pool.key(),
+        stake_position: stake_position.key(),
+        owner: stake_position.owner,
+        amount: pending_rewards,
+    });
+    
+    msg!("Claimed {} DATA staking rewards", pending_rewards);
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Pool holding one minimum stake per lockup tier, in tier order
+    fn pool_with_one_stake_per_tier() -> (StakePool, Vec<u64>) {
+        let weights: Vec<u64> = LOCKUP_TIER_WEIGHTS.iter().map(|weight| MIN_STAKE_AMOUNT * weight / 100).collect();
+        let pool = StakePool {
+            authority: Pubkey::default(),
+            stake_mint: Pubkey::default(),
+            stake_vault: Pubkey::default(),
+            reward_vault: Pubkey::default(),
+            total_staked: MIN_STAKE_AMOUNT * weights.len() as u64,
+            total_weighted_stake: weights.iter().sum(),
+            acc_reward_per_share: 0,
+            last_reward_balance: 0,
+            total_rewards_distributed: 0,
+            authority_bump: 255,
+            version: STAKING_VERSION,
+        };
+        
+        (pool, weights)
+    }
+    
+    #[test]
+    fn longer_lockups_earn_a_larger_fee_share() {
+        let (mut pool, weights) = pool_with_one_stake_per_tier();
+        
+        pool.update_rewards(6_000).unwrap();
+        
+        let rewards: Vec<u128> = weights.iter().map(|weight| pool.accrued_rewards(*weight).unwrap()).collect();
+        assert_eq!(rewards, vec![1_000, 2_000, 3_000]);
+    }
+    
+    #[test]
+    fn fees_smaller_than_the_stake_weight_stay_in_the_vault() {
+        let (mut pool, weights) = pool_with_one_stake_per_tier();
+        
+        // One base unit across 600 DATA of weight rounds every position down to nothing
+        pool.update_rewards(1).unwrap();
+        assert!(weights.iter().all(|weight| pool.accrued_rewards(*weight).unwrap() == 0));
+        
+        // The unpaid unit is still counted as received, so later fees are not double counted
+        assert_eq!(pool.last_reward_balance, 1);
+    }
+    
+    #[test]
+    fn claimed_rewards_are_not_counted_again() {
+        let (mut pool, weights) = pool_with_one_stake_per_tier();
+        pool.update_rewards(6_000).unwrap();
+        
+        // Claim the longest tier's share the way claim_rewards does
+        let claimed = pool.accrued_rewards(weights[2]).unwrap() as u64;
+        pool.last_reward_balance -= claimed;
+        let accumulator = pool.acc_reward_per_share;
+        
+        pool.update_rewards(6_000 - claimed).unwrap();
+        assert_eq!(pool.acc_reward_per_share, accumulator);
+    }
+    
+    #[test]
+    fn fees_before_the_first_stake_go_to_the_first_staker() {
+        let (mut pool, weights) = pool_with_one_stake_per_tier();
+        pool.total_weighted_stake = 0;
+        
+        pool.update_rewards(500).unwrap();
+        assert_eq!(pool.acc_reward_per_share, 0);
+        
+        pool.total_weighted_stake = weights[0];
+        pool.update_rewards(500).unwrap();
+        assert_eq!(pool.accrued_rewards(weights[0]).unwrap(), 500);
+    }
+    
+    #[test]
+    fn a_new_stake_only_earns_fees_after_it_joins() {
+        let (mut pool, weights) = pool_with_one_stake_per_tier();
+        pool.update_rewards(6_000).unwrap();
+        
+        // A second 180 day stake starts with a reward debt at the current accumulator
+        let reward_debt = pool.accrued_rewards(weights[2]).unwrap();
+        pool.total_weighted_stake += weights[2];
+        
+        pool.update_rewards(6_000 + 9_000).unwrap();
+        assert_eq!(pool.accrued_rewards(weights[2]).unwrap() - reward_debt, 3_000);
+    }
+    
+    #[test]
+    fn a_vault_balance_below_the_recorded_rewards_is_rejected() {
+        let (mut pool, _) = pool_with_one_stake_per_tier();
+        pool.update_rewards(1_000).unwrap();
+        
+        assert!(pool.update_rewards(999).is_err());
+    }
+}
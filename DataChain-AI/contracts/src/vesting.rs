@@ -0,0 +1,376 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solana_program::sysvar::clock::Clock;
+use crate::errors::DataChainError;
+use crate::protocol::{ProtocolState, PROTOCOL_STATE_SEED, PAUSE_MINTING};
+use crate::data_token::{self, TokenInfo, MINT_AUTHORITY_SEED};
+use crate::events::*;
+
+// Constants for token vesting
+pub const VESTING_VERSION: u8 = 1;
+pub const VESTING_AUTHORITY_SEED: &[u8] = b"vesting_authority";
+pub const VESTING_VAULT_SEED: &[u8] = b"vesting_vault";
+
+// Vesting schedule structure
+#[account]
+pub struct VestingSchedule {
+    // Authority that created the schedule (and may revoke it)
+    pub authority: Pubkey,
+    
+    // Wallet entitled to the vested tokens
+    pub beneficiary: Pubkey,
+    
+    // Token mint and the program-owned vault holding locked tokens
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    
+    // Allocation info
+    pub total_amount: u64,
+    pub released_amount: u64,
+    
+    // Schedule timing
+    pub start_timestamp: i64,
+    pub cliff_timestamp: i64,
+    pub duration: i64,
+    
+    // Revocation info
+    pub revocable: bool,
+    pub revoked: bool,
+    
+    // Bump of the vault authority PDA
+    pub authority_bump: u8,
+    
+    // Version for future upgrades
+    pub version: u8,
+}
+
+impl VestingSchedule {
+    // Amount vested at the given timestamp, ignoring what was already released
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if self.revoked {
+            // total_amount is frozen to the vested amount at revocation
+            return Ok(self.total_amount);
+        }
+        
+        if now < self.cliff_timestamp {
+            return Ok(0);
+        }
+        
+        let end_timestamp = self.start_timestamp.checked_add(self.duration).ok_or(DataChainError::InvalidParameters)?;
+        if now >= end_timestamp {
+            return Ok(self.total_amount);
+        }
+        
+        let elapsed = now.checked_sub(self.start_timestamp).ok_or(DataChainError::InvalidTimestamp)?;
+        let vested = (self.total_amount as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(DataChainError::InvalidParameters)?
+            .checked_div(self.duration as u128)
+            .ok_or(DataChainError::InvalidParameters)? as u64;
+        
+        Ok(vested)
+    }
+}
+
+// Context for creating a vesting schedule
+#[derive(Accounts)]
+pub struct CreateVestingSchedule<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Beneficiary wallet, only stored on the schedule
+    pub beneficiary: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_info.mint == mint.key() @ DataChainError::InvalidParameters,
+        constraint = token_info.authority == authority.key() @ DataChainError::Unauthorized,
+        constraint = token_info.mint_multisig.is_none() @ DataChainError::Unauthorized
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    /// CHECK: Program mint authority PDA
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, token_info.key().as_ref()],
+        bump = token_info.mint_authority_bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VestingSchedule>(),
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    /// CHECK: PDA signing for the vesting vault
+    #[account(
+        seeds = [VESTING_AUTHORITY_SEED, vesting_schedule.key().as_ref()],
+        bump
+    )]
+    pub vesting_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [VESTING_VAULT_SEED, vesting_schedule.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting_authority,
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.is_paused(PAUSE_MINTING) @ DataChainError::ProtocolPaused
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for claiming vested tokens
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub beneficiary: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = vesting_schedule.beneficiary == beneficiary.key() @ DataChainError::Unauthorized
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    /// CHECK: PDA signing for the vesting vault
+    #[account(
+        seeds = [VESTING_AUTHORITY_SEED, vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.authority_bump
+    )]
+    pub vesting_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == vesting_schedule.vault @ DataChainError::InvalidParameters
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = beneficiary_token.mint == vesting_schedule.mint @ DataChainError::InvalidParameters,
+        constraint = beneficiary_token.owner == beneficiary.key() @ DataChainError::Unauthorized
+    )]
+    pub beneficiary_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Context for revoking a vesting schedule
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = vesting_schedule.authority == authority.key() @ DataChainError::Unauthorized,
+        constraint = vesting_schedule.revocable @ DataChainError::VestingNotRevocable,
+        constraint = !vesting_schedule.revoked @ DataChainError::VestingAlreadyRevoked
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    
+    /// CHECK: PDA signing for the vesting vault
+    #[account(
+        seeds = [VESTING_AUTHORITY_SEED, vesting_schedule.key().as_ref()],
+        bump = vesting_schedule.authority_bump
+    )]
+    pub vesting_authority: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == vesting_schedule.vault @ DataChainError::InvalidParameters
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = refund_token.mint == vesting_schedule.mint @ DataChainError::InvalidParameters,
+        constraint = refund_token.owner == authority.key() @ DataChainError::Unauthorized
+    )]
+    pub refund_token: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Implementation of vesting functions
+pub fn create_vesting_schedule(
+    ctx: Context<CreateVestingSchedule>,
+    amount: u64,
+    start_timestamp: i64,
+    cliff_timestamp: i64,
+    duration: i64,
+    revocable: bool,
+) -> Result<()> {
+    // Validate schedule parameters
+    if amount == 0 || duration <= 0 {
+        return err!(DataChainError::InvalidParameters);
+    }
+    
+    let end_timestamp = start_timestamp.checked_add(duration).ok_or(DataChainError::InvalidParameters)?;
+    if cliff_timestamp < start_timestamp || cliff_timestamp > end_timestamp {
+        return err!(DataChainError::InvalidTimestamp);
+    }
+    
+    let token_info = &mut ctx.accounts.token_info;
+    let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+    let clock = &ctx.accounts.clock;
+    
+    // Locked allocations count against the emission cap and max supply like any other mint
+    token_info.check_mint_allowed(amount, clock.unix_timestamp)?;
+    
+    // Mint the allocation into the vesting vault
+    data_token::mint_with_program_authority(
+        token_info,
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.vesting_vault.to_account_info(),
+        &ctx.accounts.mint_authority,
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+    
+    token_info.record_mint(amount, clock.unix_timestamp)?;
+    
+    // Initialize vesting schedule
+    vesting_schedule.authority = ctx.accounts.authority.key();
+    vesting_schedule.beneficiary = ctx.accounts.beneficiary.key();
+    vesting_schedule.mint = ctx.accounts.mint.key();
+    vesting_schedule.vault = ctx.accounts.vesting_vault.key();
+    vesting_schedule.total_amount = amount;
+    vesting_schedule.released_amount = 0;
+    vesting_schedule.start_timestamp = start_timestamp;
+    vesting_schedule.cliff_timestamp = cliff_timestamp;
+    vesting_schedule.duration = duration;
+    vesting_schedule.revocable = revocable;
+    vesting_schedule.revoked = false;
+    vesting_schedule.authority_bump = *ctx.bumps.get("vesting_authority").ok_or(DataChainError::SystemError)?;
+    vesting_schedule.version = VESTING_VERSION;
+    
+    emit!(VestingScheduleCreated {
+        vesting_schedule: vesting_schedule.key(),
+        authority: vesting_schedule.authority,
+        beneficiary: vesting_schedule.beneficiary,
+        amount,
+        start_timestamp,
+        cliff_timestamp,
+        duration,
+        revocable,
+    });
+    
+    msg!("Vesting schedule created: {} DATA for {}", amount, vesting_schedule.beneficiary);
+    
+    Ok(())
+}
+
+pub fn claim_vested(
+    ctx: Context<ClaimVested>,
+) -> Result<()> {
+    let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+    let clock = &ctx.accounts.clock;
+    
+    let vested = vesting_schedule.vested_amount(clock.unix_timestamp)?;
+    let claimable = vested.checked_sub(vesting_schedule.released_amount).ok_or(DataChainError::InvalidParameters)?;
+    
+    if claimable == 0 {
+        return err!(DataChainError::NothingToClaim);
+    }
+    
+    // Release tokens from the vault
+    let schedule_key = vesting_schedule.key();
+    let seeds = &[
+        VESTING_AUTHORITY_SEED,
+        schedule_key.as_ref(),
+        &[vesting_schedule.authority_bump],
+    ];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vesting_vault.to_account_info(),
+        to: ctx.accounts.beneficiary_token.to_account_info(),
+        authority: ctx.accounts.vesting_authority.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ),
+        claimable,
+    )?;
+    
+    vesting_schedule.released_amount = vesting_schedule.released_amount.checked_add(claimable).ok_or(DataChainError::InvalidParameters)?;
+    
+    emit!(VestedTokensClaimed {
+        vesting_schedule: vesting_schedule.key(),
+        beneficiary: vesting_schedule.beneficiary,
+        amount: claimable,
+        released_amount: vesting_schedule.released_amount,
+    });
+    
+    msg!("Claimed {} vested DATA tokens for {}", claimable, vesting_schedule.beneficiary);
+    
+    Ok(())
+}
+
+pub fn revoke_vesting(
+    ctx: Context<RevokeVesting>,
+) -> Result<()> {
+    let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+    let clock = &ctx.accounts.clock;
+    
+    // Everything vested so far stays claimable by the beneficiary
+    let vested = vesting_schedule.vested_amount(clock.unix_timestamp)?;
+    let unvested = vesting_schedule.total_amount.checked_sub(vested).ok_or(DataChainError::InvalidParameters)?;
+    
+    if unvested > 0 {
+        let schedule_key = vesting_schedule.key();
+        let seeds = &[
+            VESTING_AUTHORITY_SEED,
+            schedule_key.as_ref(),
+            &[vesting_schedule.authority_bump],
+        ];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vesting_vault.to_account_info(),
+            to: ctx.accounts.refund_token.to_account_info(),
+            authority: ctx.accounts.vesting_authority.to_account_info(),
+        };
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            unvested,
+        )?;
+    }
+    
+    vesting_schedule.total_amount = vested;
+    vesting_schedule.revoked = true;
+    
+    emit!(VestingRevoked {
+        vesting_schedule: vesting_schedule.key(),
+        vested_amount: vested,
+        unvested_amount: unvested,
+    });
+    
+    msg!("Vesting schedule revoked, {} unvested DATA tokens returned", unvested);
+    
+    Ok(())
+}